@@ -0,0 +1,69 @@
+//! Throughput of bulk password generation, measured with `cargo bench`.
+//!
+//! The benchmark only uses the standard library, so it prints the number of
+//! passwords generated per second instead of a statistical report.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use securepass::{generate_random_password_with_rng, Charset, PasswordOptions};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const PASSWORDS: usize = 100_000;
+const LENGTH: usize = 16;
+
+fn main() {
+    let charset = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%^&*()-_=+";
+    let compiled = Charset::new(charset);
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+
+    report("generate_random_password", PASSWORDS, || {
+        generate_random_password_with_rng(charset, LENGTH, &mut rng).unwrap()
+    });
+    report("Charset::generate_password", PASSWORDS, || {
+        compiled.generate_password_with_rng(LENGTH, &mut rng).unwrap()
+    });
+
+    let options = PasswordOptions {
+        with_balancing: false,
+        ..Default::default()
+    };
+    report("PasswordOptions::generate_password", PASSWORDS, || {
+        options.generate_password_with_rng(&mut rng).unwrap()
+    });
+    let mut stream = options.passwords_with_rng(ChaCha20Rng::seed_from_u64(0)).unwrap();
+    report("PasswordOptions::passwords", PASSWORDS, || stream.next().unwrap().unwrap());
+
+    let start = Instant::now();
+    black_box(PasswordOptions::default().generate_unique_passwords(PASSWORDS).unwrap());
+    print_throughput("generate_unique_passwords", PASSWORDS, start.elapsed());
+
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    let start = Instant::now();
+    black_box(PasswordOptions::default().generate_unique_passwords_in_parallel(PASSWORDS, threads).unwrap());
+    print_throughput("generate_unique_passwords_in_parallel", PASSWORDS, start.elapsed());
+}
+
+/// Runs `generate` the given number of times and prints the throughput.
+fn report<F: FnMut() -> String>(name: &str, count: usize, mut generate: F) {
+    for _ in 0..count / 10 {
+        black_box(generate());
+    }
+
+    let start = Instant::now();
+    for _ in 0..count {
+        black_box(generate());
+    }
+    print_throughput(name, count, start.elapsed());
+}
+
+/// Prints the throughput of generating `count` passwords in `elapsed`.
+fn print_throughput(name: &str, count: usize, elapsed: Duration) {
+    let elapsed = elapsed.max(Duration::from_nanos(1));
+    println!(
+        "{:<40} {:>10.0} passwords/s {:>8.1} ns/password",
+        name,
+        count as f64 / elapsed.as_secs_f64(),
+        elapsed.as_nanos() as f64 / count as f64
+    );
+}
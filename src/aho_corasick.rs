@@ -0,0 +1,219 @@
+//! Aho-Corasick automaton finding every occurrence of many words in a single
+//! pass over a text.
+
+use std::collections::VecDeque;
+
+const ROOT: usize = 0;
+
+/// Structure representing an occurrence of a pattern in a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct AutomatonHit {
+    /// Index of the pattern, in the order the patterns were given.
+    pub(crate) pattern: usize,
+    /// Index of the first character of the occurrence.
+    pub(crate) start: usize,
+    /// Index one past the last character of the occurrence.
+    pub(crate) end: usize,
+}
+
+/// Structure representing a state of the automaton.
+#[derive(Debug, Default)]
+struct Node {
+    /// Transitions to child states, sorted by character.
+    transitions: Vec<(char, usize)>,
+    /// State of the longest proper suffix that is also a prefix of a pattern.
+    fail: usize,
+    /// Pattern ending in this state.
+    pattern: Option<usize>,
+    /// Nearest state on the fail chain where a pattern ends.
+    output: Option<usize>,
+}
+
+/// Structure representing an Aho-Corasick automaton over characters.
+#[derive(Debug)]
+pub(crate) struct AhoCorasick {
+    nodes: Vec<Node>,
+    pattern_lengths: Vec<usize>,
+}
+
+impl AhoCorasick {
+    /// Builds the automaton for the given patterns.
+    ///
+    /// When a pattern is given several times, only its first index is reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - The words to search for.
+    ///
+    /// # Returns
+    ///
+    /// The built `AhoCorasick` automaton.
+    pub(crate) fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut automaton = Self {
+            nodes: vec![Node::default()],
+            pattern_lengths: Vec::new(),
+        };
+
+        for (index, pattern) in patterns.into_iter().enumerate() {
+            let mut state = ROOT;
+            let mut length = 0;
+            for c in pattern.as_ref().chars() {
+                state = match automaton.transition(state, c) {
+                    Some(next) => next,
+                    None => automaton.add_transition(state, c),
+                };
+                length += 1;
+            }
+            automaton.pattern_lengths.push(length);
+            if state != ROOT && automaton.nodes[state].pattern.is_none() {
+                automaton.nodes[state].pattern = Some(index);
+            }
+        }
+
+        automaton.build_fail_links();
+        automaton
+    }
+
+    /// Finds every occurrence of every pattern, including overlapping ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The characters to search in.
+    ///
+    /// # Returns
+    ///
+    /// The occurrences ordered by their end, with character indices.
+    pub(crate) fn find_overlapping<I>(&self, text: I) -> Vec<AutomatonHit>
+    where
+        I: IntoIterator<Item = char>,
+    {
+        let mut hits = Vec::new();
+        let mut state = ROOT;
+
+        for (index, c) in text.into_iter().enumerate() {
+            state = self.next_state(state, c);
+            let mut found = self.first_output(state);
+            while let Some(node) = found {
+                if let Some(pattern) = self.nodes[node].pattern {
+                    hits.push(AutomatonHit {
+                        pattern,
+                        start: index + 1 - self.pattern_lengths[pattern],
+                        end: index + 1,
+                    });
+                }
+                found = self.nodes[node].output;
+            }
+        }
+
+        hits
+    }
+
+    /// Returns the state reached from `state` after reading `c`.
+    fn next_state(&self, mut state: usize, c: char) -> usize {
+        loop {
+            if let Some(next) = self.transition(state, c) {
+                return next;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+
+    /// Returns the first state on the fail chain of `state` where a pattern ends.
+    fn first_output(&self, state: usize) -> Option<usize> {
+        if self.nodes[state].pattern.is_some() {
+            Some(state)
+        } else {
+            self.nodes[state].output
+        }
+    }
+
+    /// Returns the child of `state` for `c`, if any.
+    fn transition(&self, state: usize, c: char) -> Option<usize> {
+        let transitions = &self.nodes[state].transitions;
+        transitions
+            .binary_search_by_key(&c, |(key, _)| *key)
+            .ok()
+            .map(|position| transitions[position].1)
+    }
+
+    /// Adds a child of `state` for `c` and returns it.
+    fn add_transition(&mut self, state: usize, c: char) -> usize {
+        let next = self.nodes.len();
+        self.nodes.push(Node::default());
+        let transitions = &mut self.nodes[state].transitions;
+        let position = transitions.partition_point(|(key, _)| *key < c);
+        transitions.insert(position, (c, next));
+        next
+    }
+
+    /// Computes the fail and output links of every state, breadth first.
+    fn build_fail_links(&mut self) {
+        let mut queue: VecDeque<usize> = self.nodes[ROOT].transitions.iter().map(|(_, child)| *child).collect();
+
+        while let Some(state) = queue.pop_front() {
+            for (c, child) in self.nodes[state].transitions.clone() {
+                let mut fallback = self.nodes[state].fail;
+                let fail = loop {
+                    if let Some(next) = self.transition(fallback, c) {
+                        break next;
+                    }
+                    if fallback == ROOT {
+                        break ROOT;
+                    }
+                    fallback = self.nodes[fallback].fail;
+                };
+                self.nodes[child].fail = fail;
+                self.nodes[child].output = self.first_output(fail);
+                queue.push_back(child);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(automaton: &AhoCorasick, text: &str) -> Vec<(usize, usize, usize)> {
+        let mut hits: Vec<(usize, usize, usize)> = automaton
+            .find_overlapping(text.chars())
+            .into_iter()
+            .map(|hit| (hit.pattern, hit.start, hit.end))
+            .collect();
+        hits.sort();
+        hits
+    }
+
+    #[test]
+    fn test_find_overlapping() {
+        let automaton = AhoCorasick::new(["he", "she", "his", "hers"]);
+        assert_eq!(found(&automaton, "ushers"), vec![(0, 2, 4), (1, 1, 4), (3, 2, 6)]);
+        assert!(found(&automaton, "xyz").is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_patterns_report_first_index() {
+        let automaton = AhoCorasick::new(["abc", "b", "abc"]);
+        assert_eq!(found(&automaton, "abc"), vec![(0, 0, 3), (1, 1, 2)]);
+    }
+
+    #[test]
+    fn test_multi_byte_characters() {
+        let automaton = AhoCorasick::new(["żółw", "łw"]);
+        assert_eq!(found(&automaton, "ażółwb"), vec![(0, 1, 5), (1, 3, 5)]);
+    }
+
+    #[test]
+    fn test_empty_patterns() {
+        let automaton = AhoCorasick::new(Vec::<String>::new());
+        assert!(found(&automaton, "anything").is_empty());
+        assert!(found(&AhoCorasick::new([""]), "a").is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+//! Streams and batches of passwords generated from the same options.
+
+use crate::{PasswordGenerator, PasswordOptions, SecurepassError, MAX_GENERATION_ATTEMPTS};
+use rand::rngs::ThreadRng;
+use rand::{thread_rng, CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::collections::HashSet;
+use std::panic;
+use std::thread;
+
+/// Structure representing an endless stream of passwords generated from
+/// the same options.
+///
+/// The charsets and dictionary of the options are prepared once when the
+/// stream is created. Every item is the result of one generation, so a
+/// stream is usually consumed with `take` and collected into a
+/// `Result<Vec<String>, SecurepassError>`.
+pub struct PasswordStream<'a, R> {
+    generator: PasswordGenerator<'a>,
+    rng: R,
+}
+
+impl<R: RngCore + CryptoRng> Iterator for PasswordStream<'_, R> {
+    type Item = Result<String, SecurepassError>;
+
+    /// Generates the next password of the stream, which never ends.
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.generator.generate_password_with_rng(&mut self.rng))
+    }
+}
+
+impl PasswordOptions {
+    /// Creates an endless stream of passwords generated from the options.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the stream, or an `Err` with
+    /// [`SecurepassError::TooShort`] if the password length is less than 10,
+    /// or the error of a failed dictionary lookup. Every item of the stream
+    /// is the same `Result` as [`PasswordOptions::generate_password`].
+    pub fn passwords(&self) -> Result<PasswordStream<'_, ThreadRng>, SecurepassError> {
+        self.passwords_with_rng(thread_rng())
+    }
+
+    /// Creates an endless stream of passwords generated from the options
+    /// using the given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A cryptographically secure random number generator, owned by the stream.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`PasswordOptions::passwords`].
+    pub fn passwords_with_rng<R: RngCore + CryptoRng>(&self, rng: R) -> Result<PasswordStream<'_, R>, SecurepassError> {
+        Ok(PasswordStream {
+            generator: self.generator()?,
+            rng,
+        })
+    }
+
+    /// Generates a batch of distinct passwords from the options.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of passwords.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the passwords in the order they were
+    /// generated, or an `Err` with the error of the first failed generation,
+    /// or [`SecurepassError::UnsatisfiableRequirements`] if 100 passwords in
+    /// a row were already in the batch, which means the options cannot give
+    /// that many distinct passwords.
+    pub fn generate_unique_passwords(&self, count: usize) -> Result<Vec<String>, SecurepassError> {
+        self.generate_unique_passwords_with_rng(count, &mut thread_rng())
+    }
+
+    /// Generates a batch of distinct passwords from the options using the
+    /// given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of passwords.
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`PasswordOptions::generate_unique_passwords`].
+    pub fn generate_unique_passwords_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        count: usize,
+        rng: &mut R,
+    ) -> Result<Vec<String>, SecurepassError> {
+        let mut batch = UniqueBatch::with_capacity(count);
+        batch.fill(&self.generator()?, count, rng)?;
+        Ok(batch.passwords)
+    }
+
+    /// Generates a batch of distinct passwords from the options across
+    /// threads.
+    ///
+    /// Every thread draws its share of the passwords with its own ChaCha20
+    /// generator seeded from the thread-local generator. The shares are then
+    /// merged, and the few passwords lost to duplicates are generated again.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of passwords.
+    /// * `threads` - The number of threads, at least one is used.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`PasswordOptions::generate_unique_passwords`].
+    pub fn generate_unique_passwords_in_parallel(
+        &self,
+        count: usize,
+        threads: usize,
+    ) -> Result<Vec<String>, SecurepassError> {
+        let generator = self.generator()?;
+        let threads = threads.clamp(1, count.max(1));
+        let mut seeds = Vec::with_capacity(threads);
+        for _ in 0..threads {
+            seeds.push(ChaCha20Rng::from_rng(thread_rng()).expect("the thread-local generator does not fail"));
+        }
+
+        let shares: Vec<Result<Vec<String>, SecurepassError>> = thread::scope(|scope| {
+            let handles: Vec<_> = seeds
+                .into_iter()
+                .enumerate()
+                .map(|(index, mut rng)| {
+                    let generator = &generator;
+                    let share = count / threads + usize::from(index < count % threads);
+                    scope.spawn(move || (0..share).map(|_| generator.generate_password_with_rng(&mut rng)).collect())
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|payload| panic::resume_unwind(payload)))
+                .collect()
+        });
+
+        let mut batch = UniqueBatch::with_capacity(count);
+        for share in shares {
+            for password in share? {
+                batch.insert(password);
+            }
+        }
+        batch.fill(&generator, count, &mut thread_rng())?;
+        Ok(batch.passwords)
+    }
+}
+
+/// Structure representing a batch of distinct passwords, in the order they
+/// were added.
+struct UniqueBatch {
+    passwords: Vec<String>,
+    seen: HashSet<String>,
+}
+
+impl UniqueBatch {
+    /// Creates an empty batch with room for `capacity` passwords.
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            passwords: Vec::with_capacity(capacity),
+            seen: HashSet::with_capacity(capacity),
+        }
+    }
+
+    /// Adds a password unless it is already in the batch, and returns whether it was added.
+    fn insert(&mut self, password: String) -> bool {
+        if self.seen.contains(&password) {
+            return false;
+        }
+        self.seen.insert(password.clone());
+        self.passwords.push(password);
+        true
+    }
+
+    /// Generates passwords until the batch has `count` of them, failing
+    /// after too many duplicates in a row.
+    fn fill<R: RngCore + CryptoRng>(
+        &mut self,
+        generator: &PasswordGenerator<'_>,
+        count: usize,
+        rng: &mut R,
+    ) -> Result<(), SecurepassError> {
+        let mut duplicates = 0;
+        while self.passwords.len() < count {
+            if self.insert(generator.generate_password_with_rng(rng)?) {
+                duplicates = 0;
+                continue;
+            }
+            duplicates += 1;
+            if duplicates >= MAX_GENERATION_ATTEMPTS {
+                return Err(SecurepassError::UnsatisfiableRequirements(format!(
+                    "only {} distinct passwords were found out of {}",
+                    self.passwords.len(),
+                    count
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{check_password_strength, PasswordStrength, PinOptions};
+
+    #[test]
+    fn test_password_stream() {
+        let options = PasswordOptions::default();
+        let passwords: Vec<String> = options
+            .passwords_with_rng(ChaCha20Rng::seed_from_u64(1))
+            .unwrap()
+            .take(20)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(passwords.len(), 20);
+        for password in &passwords {
+            assert_eq!(check_password_strength(password).unwrap(), PasswordStrength::Strong);
+        }
+        assert_ne!(passwords[0], passwords[1]);
+    }
+
+    #[test]
+    fn test_password_stream_invalid_options() {
+        let options = PasswordOptions {
+            length: 4,
+            ..Default::default()
+        };
+        assert!(matches!(options.passwords(), Err(SecurepassError::TooShort { .. })));
+    }
+
+    #[test]
+    fn test_generate_unique_passwords() {
+        let options = PasswordOptions {
+            with_balancing: false,
+            ..Default::default()
+        };
+        let passwords = options.generate_unique_passwords(500).unwrap();
+        assert_eq!(passwords.len(), 500);
+        assert_eq!(passwords.iter().collect::<HashSet<_>>().len(), 500);
+    }
+
+    #[test]
+    fn test_generate_unique_passwords_in_parallel() {
+        let options = PasswordOptions::default();
+        let passwords = options.generate_unique_passwords_in_parallel(203, 4).unwrap();
+        assert_eq!(passwords.len(), 203);
+        assert_eq!(passwords.iter().collect::<HashSet<_>>().len(), 203);
+        assert!(options.generate_unique_passwords_in_parallel(0, 4).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_generate_unique_passwords_exhausted() {
+        let options = PasswordOptions {
+            pin: Some(PinOptions { length: 4 }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            options.generate_unique_passwords(20_000),
+            Err(SecurepassError::UnsatisfiableRequirements(_))
+        ));
+    }
+}
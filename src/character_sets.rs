@@ -0,0 +1,173 @@
+//! Configurable character classes used to generate and measure passwords.
+
+use crate::{
+    normalize, Normalization, PasswordSpecification, LOWERCASE_CHARSET, NUMBERS, SPECIAL_CHARSET, UNICODE_CHARSET,
+    UPPERCASE_CHARSET,
+};
+
+/// Structure representing the characters of every class a password is made of.
+///
+/// The default sets are the ASCII letters, the digits, `!@#$%^&*?(){}[]<>-_=+`
+/// and the accented Latin letters of the Unicode class, which is only used
+/// to generate passwords when requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharacterSets {
+    /// Characters counted as lowercase letters.
+    pub lowercase: String,
+    /// Characters counted as uppercase letters.
+    pub uppercase: String,
+    /// Characters counted as digits.
+    pub numbers: String,
+    /// Characters counted as special characters.
+    pub special: String,
+    /// Characters counted as the Unicode class, in NFC.
+    pub unicode: String,
+}
+
+impl Default for CharacterSets {
+    /// Returns the default character sets.
+    fn default() -> Self {
+        Self {
+            lowercase: String::from(LOWERCASE_CHARSET),
+            uppercase: String::from(UPPERCASE_CHARSET),
+            numbers: String::from(NUMBERS),
+            special: String::from(SPECIAL_CHARSET),
+            unicode: String::from(UNICODE_CHARSET),
+        }
+    }
+}
+
+impl CharacterSets {
+    /// Returns the sets of every class, in the order lowercase, uppercase,
+    /// numbers, special, Unicode.
+    pub(crate) fn classes(&self) -> [&str; 5] {
+        [&self.lowercase, &self.uppercase, &self.numbers, &self.special, &self.unicode]
+    }
+
+    /// Checks which of the configured classes a password contains, after
+    /// normalizing it to NFC.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A string slice representing the password.
+    ///
+    /// # Returns
+    ///
+    /// A `PasswordSpecification` structure containing the specifications of the password.
+    pub fn check_password_specification(&self, password: &str) -> PasswordSpecification {
+        let password = normalize(password, Normalization::Nfc);
+        let contains_any = |set: &str| password.chars().any(|c| set.contains(c));
+
+        PasswordSpecification {
+            has_lowercase: contains_any(&self.lowercase),
+            has_uppercase: contains_any(&self.uppercase),
+            has_number: contains_any(&self.numbers),
+            has_special: contains_any(&self.special),
+            has_unicode: contains_any(&self.unicode),
+        }
+    }
+
+    /// Calculates the entropy of a password drawn from the configured classes
+    /// it contains, counting the characters of the password normalized to NFC.
+    ///
+    /// Characters outside every class, like CJK, emoji or titlecase letters,
+    /// add the number of distinct ones in the password to the pool, since
+    /// their alphabet is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A string slice representing the password.
+    ///
+    /// # Returns
+    ///
+    /// The entropy of the password as a `f64`, `0.0` for an empty password.
+    pub fn calculate_entropy(&self, password: &str) -> f64 {
+        let password = normalize(password, Normalization::Nfc);
+        let classes = self.classes();
+        let present = classes.map(|set| password.chars().any(|c| set.contains(c)));
+        let mut other: Vec<char> = Vec::new();
+        for c in password.chars() {
+            if !classes.iter().any(|set| set.contains(c)) && !other.contains(&c) {
+                other.push(c);
+            }
+        }
+        let pool_size = self.pool_size(present) + other.len();
+        let length = password.chars().count();
+        if pool_size == 0 || length == 0 {
+            return 0.0;
+        }
+
+        length as f64 * (pool_size as f64).log2()
+    }
+
+    /// Counts the distinct characters of the selected classes.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - Whether each class, in the order of [`CharacterSets::classes`], is counted.
+    ///
+    /// # Returns
+    ///
+    /// The number of distinct characters.
+    pub(crate) fn pool_size(&self, selected: [bool; 5]) -> usize {
+        let mut pool: Vec<char> = Vec::new();
+        for (set, _) in self.classes().into_iter().zip(selected).filter(|(_, selected)| *selected) {
+            for c in set.chars() {
+                if !pool.contains(&c) {
+                    pool.push(c);
+                }
+            }
+        }
+
+        pool.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calculate_entropy, check_password_specification};
+
+    #[test]
+    fn test_default_sets_match_free_functions() {
+        let sets = CharacterSets::default();
+        for password in ["!QEa4Kta2}wg1", "weakpassword", "Medium333!@"] {
+            assert_eq!(sets.check_password_specification(password), check_password_specification(password));
+            assert!((sets.calculate_entropy(password) - calculate_entropy(password)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_custom_sets() {
+        let sets = CharacterSets {
+            special: String::from(".,;"),
+            ..Default::default()
+        };
+
+        let specification = sets.check_password_specification("abc.");
+        assert!(specification.has_lowercase && specification.has_special);
+        assert!(!sets.check_password_specification("abc!").has_special);
+        assert!((sets.calculate_entropy("abc.") - 4.0 * 29f64.log2()).abs() < 1e-9);
+        assert_eq!(sets.calculate_entropy("!!!"), 0.0);
+        assert!((sets.calculate_entropy("abc!?") - 5.0 * 28f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unicode_set() {
+        let sets = CharacterSets::default();
+
+        assert!(sets.check_password_specification("ta\u{308}").has_unicode);
+        assert!((sets.calculate_entropy("tä") - 2.0 * 148f64.log2()).abs() < 1e-9);
+        assert_eq!(sets.calculate_entropy("ta\u{308}"), sets.calculate_entropy("tä"));
+    }
+
+    #[test]
+    fn test_overlapping_sets_are_counted_once() {
+        let sets = CharacterSets {
+            special: String::from("abc!"),
+            ..Default::default()
+        };
+
+        assert!((sets.calculate_entropy("ab!") - 3.0 * 27f64.log2()).abs() < 1e-9);
+    }
+}
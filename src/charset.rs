@@ -0,0 +1,188 @@
+//! Precompiled character sets that passwords are drawn from.
+
+use crate::SecurepassError;
+use rand::{thread_rng, CryptoRng, Rng, RngCore};
+
+/// Structure representing a precompiled set of characters.
+///
+/// The characters are deduplicated and stored in the order they first appear
+/// in, so drawing one is a single uniform index into them. A charset is
+/// meant to be built once and reused for many passwords.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Charset {
+    chars: Vec<char>,
+}
+
+impl Charset {
+    /// Creates a charset from the characters of a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `charset` - A string slice representing the characters, where
+    ///   repeated characters are kept once.
+    ///
+    /// # Returns
+    ///
+    /// The precompiled charset.
+    pub fn new(charset: &str) -> Self {
+        charset.chars().collect()
+    }
+
+    /// Returns the number of distinct characters of the charset.
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// Returns whether the charset has no character.
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Returns the characters of the charset, in the order they first appeared in.
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// Returns whether the charset contains a character.
+    pub fn contains(&self, c: char) -> bool {
+        self.chars.contains(&c)
+    }
+
+    /// Returns the entropy in bits of one character drawn from the charset.
+    pub fn bits_per_char(&self) -> f64 {
+        if self.chars.is_empty() {
+            return 0.0;
+        }
+        (self.chars.len() as f64).log2()
+    }
+
+    /// Draws one character uniformly from the charset.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` which is `Some` with the character, or `None` if the
+    /// charset is empty.
+    pub fn sample<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Option<char> {
+        if self.chars.is_empty() {
+            return None;
+        }
+        Some(self.chars[rng.gen_range(0..self.chars.len())])
+    }
+
+    /// Generates a random password of the given length from the charset.
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The length of the password.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the generated password, or an `Err` with
+    /// [`SecurepassError::EmptyCharset`] if characters were requested from an
+    /// empty charset.
+    pub fn generate_password(&self, length: usize) -> Result<String, SecurepassError> {
+        self.generate_password_with_rng(length, &mut thread_rng())
+    }
+
+    /// Generates a random password of the given length from the charset
+    /// using the given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The length of the password.
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`Charset::generate_password`].
+    pub fn generate_password_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        length: usize,
+        rng: &mut R,
+    ) -> Result<String, SecurepassError> {
+        let mut password = String::with_capacity(length);
+        self.extend_with_rng(&mut password, length, rng)?;
+        Ok(password)
+    }
+
+    /// Appends `count` random characters of the charset to a collection.
+    pub(crate) fn extend_with_rng<E: Extend<char>, R: RngCore + CryptoRng>(
+        &self,
+        output: &mut E,
+        count: usize,
+        rng: &mut R,
+    ) -> Result<(), SecurepassError> {
+        if self.chars.is_empty() && count > 0 {
+            return Err(SecurepassError::EmptyCharset);
+        }
+        output.extend((0..count).map(|_| self.chars[rng.gen_range(0..self.chars.len())]));
+        Ok(())
+    }
+}
+
+impl From<&str> for Charset {
+    /// Creates a charset from the characters of a string, see [`Charset::new`].
+    fn from(charset: &str) -> Self {
+        Self::new(charset)
+    }
+}
+
+impl FromIterator<char> for Charset {
+    /// Creates a charset from characters, keeping repeated characters once.
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut chars: Vec<char> = Vec::new();
+        for c in iter {
+            if !chars.contains(&c) {
+                chars.push(c);
+            }
+        }
+        Self { chars }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_charset_is_deduplicated() {
+        let charset = Charset::new("abcabca");
+        assert_eq!(charset.chars(), ['a', 'b', 'c']);
+        assert_eq!(charset.len(), 3);
+        assert!(charset.contains('b') && !charset.contains('d'));
+        assert_eq!(Charset::new("ééé").len(), 1);
+    }
+
+    #[test]
+    fn test_charset_samples_uniformly() {
+        let charset = Charset::new("aaaab");
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        let password = charset.generate_password_with_rng(10_000, &mut rng).unwrap();
+        let count = password.chars().filter(|c| *c == 'b').count();
+        assert!((4_500..5_500).contains(&count), "{} of 10000 characters were 'b'", count);
+    }
+
+    #[test]
+    fn test_charset_generate_password() {
+        let charset = Charset::new("0123456789");
+        let password = charset.generate_password(12).unwrap();
+        assert_eq!(password.len(), 12);
+        assert!(password.chars().all(|c| charset.contains(c)));
+        assert_eq!(charset.bits_per_char(), 10f64.log2());
+    }
+
+    #[test]
+    fn test_empty_charset() {
+        let charset = Charset::new("");
+        assert!(charset.is_empty());
+        assert_eq!(charset.sample(&mut thread_rng()), None);
+        assert_eq!(charset.generate_password(0).unwrap(), "");
+        assert!(matches!(charset.generate_password(1), Err(SecurepassError::EmptyCharset)));
+    }
+}
@@ -0,0 +1,206 @@
+//! Estimated time to crack a password for several attacker models.
+
+use crate::PasswordEstimate;
+
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = MINUTE * 60.0;
+const DAY: f64 = HOUR * 24.0;
+const MONTH: f64 = DAY * 31.0;
+const YEAR: f64 = MONTH * 12.0;
+const CENTURY: f64 = YEAR * 100.0;
+
+/// Enum representing the attacker model used for a crack-time estimate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackScenario {
+    /// Online attack against a service that rate-limits login attempts.
+    OnlineThrottled,
+    /// Online attack against a service without rate limiting.
+    OnlineUnthrottled,
+    /// Offline attack against a slow hash like bcrypt, scrypt or argon2.
+    OfflineSlowHash,
+    /// Offline attack against a fast hash like SHA-1 or MD5 on many GPUs.
+    OfflineFastHash,
+}
+
+impl AttackScenario {
+    /// Returns the number of guesses per second the attacker can make.
+    pub fn guesses_per_second(&self) -> f64 {
+        match self {
+            Self::OnlineThrottled => 100.0 / HOUR,
+            Self::OnlineUnthrottled => 10.0,
+            Self::OfflineSlowHash => 1e4,
+            Self::OfflineFastHash => 1e10,
+        }
+    }
+}
+
+/// Structure representing the estimated time to crack a password in one scenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrackTime {
+    /// Attacker model of the estimate.
+    pub scenario: AttackScenario,
+    /// Estimated time in seconds.
+    pub seconds: f64,
+    /// Estimated time as a human-readable string, like "3 hours" or "centuries".
+    pub display: String,
+}
+
+/// Structure representing the estimated time to crack a password in every scenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrackTimes {
+    /// Estimate for [`AttackScenario::OnlineThrottled`].
+    pub online_throttled: CrackTime,
+    /// Estimate for [`AttackScenario::OnlineUnthrottled`].
+    pub online_unthrottled: CrackTime,
+    /// Estimate for [`AttackScenario::OfflineSlowHash`].
+    pub offline_slow_hash: CrackTime,
+    /// Estimate for [`AttackScenario::OfflineFastHash`].
+    pub offline_fast_hash: CrackTime,
+}
+
+impl CrackTimes {
+    /// Returns the estimate for the given scenario.
+    pub fn get(&self, scenario: AttackScenario) -> &CrackTime {
+        match scenario {
+            AttackScenario::OnlineThrottled => &self.online_throttled,
+            AttackScenario::OnlineUnthrottled => &self.online_unthrottled,
+            AttackScenario::OfflineSlowHash => &self.offline_slow_hash,
+            AttackScenario::OfflineFastHash => &self.offline_fast_hash,
+        }
+    }
+}
+
+impl PasswordEstimate {
+    /// Estimates the time to crack the password in every scenario.
+    ///
+    /// # Returns
+    ///
+    /// The estimates as a `CrackTimes` structure.
+    pub fn crack_times(&self) -> CrackTimes {
+        estimate_crack_times(self.guesses)
+    }
+}
+
+/// Estimates the time to crack a password needing the given number of guesses.
+///
+/// # Arguments
+///
+/// * `guesses` - Number of guesses needed to find the password, e.g. from
+///   [`crate::estimate_password_strength`].
+///
+/// # Returns
+///
+/// The estimates as a `CrackTimes` structure.
+pub fn estimate_crack_times(guesses: f64) -> CrackTimes {
+    CrackTimes {
+        online_throttled: crack_time(AttackScenario::OnlineThrottled, guesses),
+        online_unthrottled: crack_time(AttackScenario::OnlineUnthrottled, guesses),
+        offline_slow_hash: crack_time(AttackScenario::OfflineSlowHash, guesses),
+        offline_fast_hash: crack_time(AttackScenario::OfflineFastHash, guesses),
+    }
+}
+
+/// Estimates the time to crack a password with the given entropy.
+///
+/// An attacker searching a space of `2^entropy` candidates finds the password
+/// after trying half of them on average.
+///
+/// # Arguments
+///
+/// * `entropy` - Entropy in bits, e.g. from [`crate::calculate_entropy`].
+///
+/// # Returns
+///
+/// The estimates as a `CrackTimes` structure.
+pub fn estimate_crack_times_from_entropy(entropy: f64) -> CrackTimes {
+    estimate_crack_times(2f64.powf(entropy - 1.0).max(1.0))
+}
+
+/// Builds the estimate for one scenario.
+fn crack_time(scenario: AttackScenario, guesses: f64) -> CrackTime {
+    let seconds = guesses / scenario.guesses_per_second();
+
+    CrackTime {
+        scenario,
+        seconds,
+        display: display_time(seconds),
+    }
+}
+
+/// Formats a duration in seconds as a human-readable string.
+///
+/// # Arguments
+///
+/// * `seconds` - Duration in seconds.
+///
+/// # Returns
+///
+/// A string like "less than a second", "1 minute", "5 days" or "centuries".
+fn display_time(seconds: f64) -> String {
+    let (amount, unit) = if seconds < 1.0 {
+        return String::from("less than a second");
+    } else if seconds < MINUTE {
+        (seconds, "second")
+    } else if seconds < HOUR {
+        (seconds / MINUTE, "minute")
+    } else if seconds < DAY {
+        (seconds / HOUR, "hour")
+    } else if seconds < MONTH {
+        (seconds / DAY, "day")
+    } else if seconds < YEAR {
+        (seconds / MONTH, "month")
+    } else if seconds < CENTURY {
+        (seconds / YEAR, "year")
+    } else {
+        return String::from("centuries");
+    };
+
+    let amount = amount.round() as u64;
+    if amount == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", amount, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate_password_strength;
+
+    #[test]
+    fn test_estimate_crack_times() {
+        let times = estimate_crack_times(1e6);
+        assert!((times.online_throttled.seconds - 1e6 * 36.0).abs() < 1e-3);
+        assert_eq!(times.online_unthrottled.display, "1 day");
+        assert_eq!(times.offline_slow_hash.display, "2 minutes");
+        assert_eq!(times.offline_fast_hash.display, "less than a second");
+        assert_eq!(times.get(AttackScenario::OfflineFastHash).scenario, AttackScenario::OfflineFastHash);
+    }
+
+    #[test]
+    fn test_estimate_crack_times_from_entropy() {
+        let times = estimate_crack_times_from_entropy(101.0);
+        assert_eq!(times.offline_fast_hash.display, "centuries");
+        assert_eq!(estimate_crack_times_from_entropy(0.0).online_unthrottled.seconds, 0.1);
+    }
+
+    #[test]
+    fn test_password_estimate_crack_times() {
+        let weak = estimate_password_strength("Password1!").unwrap().crack_times();
+        let strong = estimate_password_strength("!QEa4Kta2}wg1").unwrap().crack_times();
+        assert!(weak.offline_slow_hash.seconds < strong.offline_slow_hash.seconds);
+        assert_eq!(weak.offline_fast_hash.display, "less than a second");
+    }
+
+    #[test]
+    fn test_display_time() {
+        assert_eq!(display_time(0.5), "less than a second");
+        assert_eq!(display_time(1.0), "1 second");
+        assert_eq!(display_time(90.0), "2 minutes");
+        assert_eq!(display_time(3.0 * HOUR), "3 hours");
+        assert_eq!(display_time(2.0 * MONTH), "2 months");
+        assert_eq!(display_time(5.0 * YEAR), "5 years");
+        assert_eq!(display_time(200.0 * YEAR), "centuries");
+    }
+}
@@ -0,0 +1,703 @@
+//! Loading and caching of the word lists used by the crate, and layered
+//! dictionaries built from them.
+//!
+//! With the `embedded-dictionary` feature (enabled by default) the lists are
+//! compiled into the binary. Without it they are read from the crate
+//! directory the first time they are needed. Either way every list is parsed
+//! only once and then shared.
+
+use crate::aho_corasick::AhoCorasick;
+use crate::{SecurepassError, SubstitutionTable};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+#[cfg_attr(feature = "embedded-dictionary", allow(dead_code))]
+const COMMON_WORDS_FILE: &str = "dictionary.txt";
+#[cfg_attr(feature = "embedded-dictionary", allow(dead_code))]
+const PASSPHRASE_WORDS_FILE: &str = "wordlist.txt";
+#[cfg_attr(feature = "embedded-dictionary", allow(dead_code))]
+const COMMON_PINS_FILE: &str = "pins.txt";
+
+#[cfg(feature = "embedded-dictionary")]
+const EMBEDDED_COMMON_WORDS: &str = include_str!("../dictionary.txt");
+#[cfg(feature = "embedded-dictionary")]
+const EMBEDDED_PASSPHRASE_WORDS: &str = include_str!("../wordlist.txt");
+#[cfg(feature = "embedded-dictionary")]
+const EMBEDDED_COMMON_PINS: &str = include_str!("../pins.txt");
+
+static COMMON_WORDS: OnceLock<Arc<WordList>> = OnceLock::new();
+static PASSPHRASE_WORDS: OnceLock<Arc<WordList>> = OnceLock::new();
+static COMMON_PINS: OnceLock<Arc<WordList>> = OnceLock::new();
+static COMMON_WORDS_OVERRIDE: RwLock<Option<Arc<WordList>>> = RwLock::new(None);
+
+/// Minimum number of characters of a part of a user input added to a dictionary.
+const MIN_USER_INPUT_PART_LENGTH: usize = 3;
+
+/// Structure representing an occurrence of a common word in a password.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryHit {
+    /// The dictionary word that was found, in lowercase.
+    pub word: String,
+    /// Rank of the word in its dictionary layer, starting from 1.
+    pub rank: usize,
+    /// Index of the first character of the word in the password.
+    pub start: usize,
+    /// Index one past the last character of the word in the password.
+    pub end: usize,
+    /// Whether the word is written backwards in the password.
+    pub reversed: bool,
+    /// The l33t substitutions undone to find the word, as `(symbol, letter)`
+    /// pairs like `('@', 'a')`.
+    pub l33t_substitutions: Vec<(char, char)>,
+    /// Whether the word comes from a layer added with
+    /// [`Dictionary::with_user_inputs`].
+    pub user_input: bool,
+    /// Whether the word comes from the built-in common words, or the list
+    /// given to [`use_dictionary_file`] in their place.
+    pub builtin: bool,
+}
+
+/// Structure representing a parsed word list.
+#[derive(Debug)]
+pub(crate) struct WordList {
+    /// Words in file order.
+    pub(crate) words: Vec<String>,
+    /// Lowercase words with their rank, starting from 1.
+    pub(crate) ranks: HashMap<String, usize>,
+    /// Automaton over the lowercase `words`, built on first use.
+    matcher: OnceLock<AhoCorasick>,
+    /// Whether the words are inputs about a user rather than common words.
+    user_input: bool,
+    /// Whether the words are the common words of [`Dictionary::builtin`].
+    builtin: bool,
+}
+
+impl WordList {
+    /// Parses a word list.
+    ///
+    /// Every non-empty line contributes its last whitespace-separated field, so
+    /// both plain lists and EFF-style lists prefixed with dice rolls are supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - A string slice with the contents of the list.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `WordList`.
+    pub(crate) fn parse(contents: &str) -> Self {
+        Self::from_words(
+            contents
+                .lines()
+                .filter_map(|line| line.split_whitespace().last())
+                .map(String::from)
+                .collect(),
+        )
+    }
+
+    /// Builds a word list from words ordered from most to least common.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - The words of the list.
+    ///
+    /// # Returns
+    ///
+    /// The `WordList` with the given words.
+    fn from_words(words: Vec<String>) -> Self {
+        let mut ranks = HashMap::with_capacity(words.len());
+        for (index, word) in words.iter().enumerate() {
+            ranks.entry(word.to_lowercase()).or_insert(index + 1);
+        }
+
+        Self {
+            words,
+            ranks,
+            matcher: OnceLock::new(),
+            user_input: false,
+            builtin: false,
+        }
+    }
+
+    /// Marks the words as the common words of [`Dictionary::builtin`].
+    fn into_builtin(mut self) -> Self {
+        self.builtin = true;
+        self
+    }
+
+    /// Returns the automaton over the lowercase words, where pattern `i` has
+    /// rank `i + 1`. Words repeated with other casing are reported only once.
+    fn matcher(&self) -> &AhoCorasick {
+        self.matcher
+            .get_or_init(|| AhoCorasick::new(self.words.iter().map(|word| word.to_lowercase())))
+    }
+}
+
+/// Structure representing a dictionary of guessable words, made of layers.
+///
+/// Every layer keeps its own ranking, so a word that is first in a small
+/// layer, like a company name, counts as very guessable even when the
+/// built-in list is much larger. Cloning a `Dictionary` shares its layers.
+///
+/// Passwords are normalized before lookup: case is folded, and words are
+/// also searched backwards and with the l33t substitutions of the
+/// dictionary's [`SubstitutionTable`] undone, so "PASSWORD", "drowssap" and
+/// "p@ssw0rd" all contain "password".
+#[derive(Clone, Default)]
+pub struct Dictionary {
+    layers: Vec<Arc<WordList>>,
+    substitutions: SubstitutionTable,
+}
+
+impl Dictionary {
+    /// Creates a dictionary without any word.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Dictionary`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a dictionary with the built-in common words, or the file set
+    /// with [`use_dictionary_file`].
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the `Dictionary`, or an `Err` if the
+    /// dictionary has to be read from disk and cannot be.
+    pub fn builtin() -> Result<Self, SecurepassError> {
+        Ok(Self {
+            layers: vec![common_words()?],
+            substitutions: SubstitutionTable::default(),
+        })
+    }
+
+    /// Creates a dictionary from words ordered from most to least common.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - The words of the dictionary. Surrounding whitespace is
+    ///   trimmed and empty words are skipped.
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` with a single layer.
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words = words
+            .into_iter()
+            .map(|word| word.as_ref().trim().to_string())
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        Self {
+            layers: vec![Arc::new(WordList::from_words(words))],
+            substitutions: SubstitutionTable::default(),
+        }
+    }
+
+    /// Creates a dictionary from the contents of a word list file.
+    ///
+    /// The contents are parsed like [`use_dictionary_file`] does: one word per
+    /// line, optionally prefixed with dice rolls. Invalid UTF-8 is replaced
+    /// with U+FFFD.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte slice with the contents of the list.
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` with a single layer.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            layers: vec![Arc::new(WordList::parse(&String::from_utf8_lossy(bytes)))],
+            substitutions: SubstitutionTable::default(),
+        }
+    }
+
+    /// Creates a dictionary from a word list file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of a file with one word per line, most common first.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with a `Dictionary` with a single layer, or an
+    /// `Err` with [`SecurepassError::DictionaryIo`] if the file cannot be read.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SecurepassError> {
+        Ok(Self {
+            layers: vec![Arc::new(read_word_list(path.as_ref())?)],
+            substitutions: SubstitutionTable::default(),
+        })
+    }
+
+    /// Adds the layers of another dictionary on top of this one, keeping the
+    /// substitution table of this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `dictionary` - The dictionary to merge, e.g. company names or
+    ///   product terms.
+    ///
+    /// # Returns
+    ///
+    /// The merged `Dictionary`.
+    pub fn with_layer(mut self, dictionary: Dictionary) -> Self {
+        self.layers.extend(dictionary.layers);
+        self
+    }
+
+    /// Adds a layer with context about a user, like their name, username or
+    /// email address.
+    ///
+    /// Every input is added as a whole, followed by its alphanumeric parts of
+    /// at least three characters, so "jane.doe@acme.com" also adds "jane",
+    /// "doe", "acme" and "com".
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The user inputs, most relevant first.
+    ///
+    /// # Returns
+    ///
+    /// The merged `Dictionary`.
+    pub fn with_user_inputs<I, S>(mut self, inputs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut words = Vec::new();
+        for input in inputs {
+            let input = input.as_ref().trim();
+            words.push(input.to_string());
+            words.extend(
+                input
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|part| part.chars().count() >= MIN_USER_INPUT_PART_LENGTH && *part != input)
+                    .map(String::from),
+            );
+        }
+
+        let mut layer = WordList::from_words(words);
+        layer.user_input = true;
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
+    /// Replaces the l33t substitutions undone before lookup.
+    ///
+    /// # Arguments
+    ///
+    /// * `substitutions` - The table to use. An empty table disables l33t matching.
+    ///
+    /// # Returns
+    ///
+    /// The `Dictionary` with the new table.
+    pub fn with_substitutions(mut self, substitutions: SubstitutionTable) -> Self {
+        self.substitutions = substitutions;
+        self
+    }
+
+    /// Returns the l33t substitutions undone before lookup.
+    pub fn substitutions(&self) -> &SubstitutionTable {
+        &self.substitutions
+    }
+
+    /// Returns the number of words in all layers.
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(|layer| layer.words.len()).sum()
+    }
+
+    /// Returns `true` if no layer has any word.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if a password contains a word of the dictionary, ignoring case,
+    /// reversal and l33t substitutions.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A string slice representing the password.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the password contains a word of any layer, `false` otherwise.
+    pub fn occurs_in(&self, password: &str) -> bool {
+        !self.find(password).is_empty()
+    }
+
+    /// Checks if a whole password is a word of the dictionary, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A string slice representing the password.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the password is a word of any layer, `false` otherwise.
+    pub fn contains(&self, password: &str) -> bool {
+        let password = password.to_lowercase();
+
+        self.layers.iter().any(|layer| layer.ranks.contains_key(&password))
+    }
+
+    /// Finds every word of the dictionary in a password, ignoring case,
+    /// reversal and l33t substitutions.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A string slice representing the password.
+    ///
+    /// # Returns
+    ///
+    /// Every occurrence, including overlapping ones, ordered by their end.
+    pub fn find(&self, password: &str) -> Vec<DictionaryHit> {
+        let chars: Vec<char> = password.chars().collect();
+
+        self.find_chars(&chars)
+    }
+
+    /// Finds every word of the dictionary in the characters of a password.
+    ///
+    /// Case is folded first. The folded password is then searched as is,
+    /// backwards, and once for every reading of its l33t symbols. Words of a
+    /// single character are not reported for l33t readings.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - The characters of the password.
+    ///
+    /// # Returns
+    ///
+    /// Every occurrence, including overlapping ones, ordered by their end.
+    pub(crate) fn find_chars(&self, chars: &[char]) -> Vec<DictionaryHit> {
+        let folded: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+        let mut hits = self.lookup(&folded);
+
+        let backwards: Vec<char> = folded.iter().rev().copied().collect();
+        hits.extend(self.lookup(&backwards).into_iter().map(|mut hit| {
+            (hit.start, hit.end) = (chars.len() - hit.end, chars.len() - hit.start);
+            hit.reversed = true;
+            hit
+        }));
+
+        for substitution in self.substitutions.combinations(&folded) {
+            let translated: Vec<char> = folded
+                .iter()
+                .map(|c| {
+                    substitution
+                        .iter()
+                        .find(|(symbol, _)| symbol == c)
+                        .map_or(*c, |(_, letter)| *letter)
+                })
+                .collect();
+
+            for mut hit in self.lookup(&translated) {
+                let token = &folded[hit.start..hit.end];
+                hit.l33t_substitutions = substitution
+                    .iter()
+                    .filter(|(symbol, _)| token.contains(symbol))
+                    .copied()
+                    .collect();
+                if !hit.l33t_substitutions.is_empty() && token.len() > 1 && !hits.contains(&hit) {
+                    hits.push(hit);
+                }
+            }
+        }
+
+        hits.sort_by_key(|hit| (hit.end, hit.start));
+        hits
+    }
+
+    /// Looks up the words of every layer in already normalized characters.
+    fn lookup(&self, chars: &[char]) -> Vec<DictionaryHit> {
+        self.layers
+            .iter()
+            .flat_map(|layer| {
+                layer
+                    .matcher()
+                    .find_overlapping(chars.iter().copied())
+                    .into_iter()
+                    .map(|hit| DictionaryHit {
+                        word: chars[hit.start..hit.end].iter().collect(),
+                        rank: hit.pattern + 1,
+                        start: hit.start,
+                        end: hit.end,
+                        reversed: false,
+                        l33t_substitutions: Vec::new(),
+                        user_input: layer.user_input,
+                        builtin: layer.builtin,
+                    })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let layer_sizes: Vec<usize> = self.layers.iter().map(|layer| layer.words.len()).collect();
+
+        f.debug_struct("Dictionary")
+            .field("layer_sizes", &layer_sizes)
+            .field("substitutions", &self.substitutions)
+            .finish()
+    }
+}
+
+/// Finds every common word in a password in a single pass.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with every occurrence, including overlapping
+/// ones, ordered by their end, or an `Err` if the dictionary cannot be read.
+pub fn find_common_words(password: &str) -> Result<Vec<DictionaryHit>, SecurepassError> {
+    Ok(Dictionary::builtin()?.find(password))
+}
+
+/// Replaces the common words dictionary with a file read at runtime.
+///
+/// The file is read and parsed once, and then used by every check of the
+/// crate instead of the bundled `dictionary.txt`.
+///
+/// # Arguments
+///
+/// * `path` - Path of a file with one word per line, most common first.
+///
+/// # Returns
+///
+/// `Ok(())` if the file was loaded, or an `Err` with
+/// [`SecurepassError::DictionaryIo`] if it cannot be read.
+pub fn use_dictionary_file<P: AsRef<Path>>(path: P) -> Result<(), SecurepassError> {
+    let words = read_word_list(path.as_ref())?;
+    let mut common_words = COMMON_WORDS_OVERRIDE.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *common_words = Some(Arc::new(words.into_builtin()));
+
+    Ok(())
+}
+
+/// Returns the common words dictionary.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with the shared word list, or an `Err` if the
+/// dictionary has to be read from disk and cannot be.
+pub(crate) fn common_words() -> Result<Arc<WordList>, SecurepassError> {
+    let common_words = COMMON_WORDS_OVERRIDE.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(words) = common_words.as_ref() {
+        return Ok(Arc::clone(words));
+    }
+
+    #[cfg(feature = "embedded-dictionary")]
+    let load = || Ok(WordList::parse(EMBEDDED_COMMON_WORDS).into_builtin());
+    #[cfg(not(feature = "embedded-dictionary"))]
+    let load = || read_bundled_word_list(COMMON_WORDS_FILE).map(WordList::into_builtin);
+
+    cached(&COMMON_WORDS, load)
+}
+
+/// Returns the passphrase word list.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with the shared word list, or an `Err` if the
+/// list has to be read from disk and cannot be.
+pub(crate) fn passphrase_words() -> Result<Arc<WordList>, SecurepassError> {
+    #[cfg(feature = "embedded-dictionary")]
+    let load = || Ok(WordList::parse(EMBEDDED_PASSPHRASE_WORDS));
+    #[cfg(not(feature = "embedded-dictionary"))]
+    let load = || read_bundled_word_list(PASSPHRASE_WORDS_FILE);
+
+    cached(&PASSPHRASE_WORDS, load)
+}
+
+/// Returns the list of common PINs.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with the shared list, or an `Err` if the list
+/// has to be read from disk and cannot be.
+pub(crate) fn common_pins() -> Result<Arc<WordList>, SecurepassError> {
+    #[cfg(feature = "embedded-dictionary")]
+    let load = || Ok(WordList::parse(EMBEDDED_COMMON_PINS));
+    #[cfg(not(feature = "embedded-dictionary"))]
+    let load = || read_bundled_word_list(COMMON_PINS_FILE);
+
+    cached(&COMMON_PINS, load)
+}
+
+/// Returns the list stored in `cell`, loading it first if needed.
+fn cached<F>(cell: &OnceLock<Arc<WordList>>, load: F) -> Result<Arc<WordList>, SecurepassError>
+where
+    F: FnOnce() -> Result<WordList, SecurepassError>,
+{
+    if let Some(words) = cell.get() {
+        return Ok(Arc::clone(words));
+    }
+    let words = Arc::new(load()?);
+
+    Ok(Arc::clone(cell.get_or_init(|| words)))
+}
+
+/// Reads a word list shipped in the crate directory.
+#[cfg_attr(feature = "embedded-dictionary", allow(dead_code))]
+fn read_bundled_word_list(file_name: &str) -> Result<WordList, SecurepassError> {
+    read_word_list(&Path::new(env!("CARGO_MANIFEST_DIR")).join(file_name))
+}
+
+/// Reads and parses a word list file.
+fn read_word_list(path: &Path) -> Result<WordList, SecurepassError> {
+    let contents = fs::read_to_string(path).map_err(|source| SecurepassError::DictionaryIo {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    Ok(WordList::parse(&contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_words_are_shared() {
+        let first = common_words().unwrap();
+        let second = common_words().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.words[0], "123456");
+        assert_eq!(first.ranks.get("password"), Some(&2));
+    }
+
+    #[test]
+    fn test_passphrase_words() {
+        let words = passphrase_words().unwrap();
+        assert_eq!(words.words.len(), 1296);
+        assert!(Arc::ptr_eq(&words, &passphrase_words().unwrap()));
+    }
+
+    #[test]
+    fn test_parse_dice_word_list() {
+        let words = WordList::parse("1111\tapple\n\n1112\tBanana\n");
+        assert_eq!(words.words, vec!["apple", "Banana"]);
+        assert_eq!(words.ranks.get("banana"), Some(&2));
+    }
+
+    #[test]
+    fn test_find_common_words() {
+        let hits = find_common_words("xdragon1").unwrap();
+        assert!(hits.contains(&DictionaryHit {
+            word: String::from("dragon"),
+            rank: 10,
+            start: 1,
+            end: 7,
+            reversed: false,
+            l33t_substitutions: Vec::new(),
+            user_input: false,
+            builtin: true,
+        }));
+        assert!(hits.iter().all(|hit| "xdragon1".get(hit.start..hit.end) == Some(hit.word.as_str())));
+        assert!(find_common_words("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_normalized_words() {
+        let dictionary = Dictionary::from_words(["password"]);
+        for password in ["PASSWORD", "drowssap", "P@ssw0rd", "xPaSsWoRdx"] {
+            assert!(dictionary.occurs_in(password), "{}", password);
+        }
+
+        let reversed = dictionary.find("1drowssap");
+        assert_eq!((reversed[0].start, reversed[0].end, reversed[0].reversed), (1, 9, true));
+        assert_eq!(reversed[0].word, "password");
+
+        let l33t = dictionary.find("p@ssw0rd");
+        assert_eq!(l33t[0].l33t_substitutions, vec![('0', 'o'), ('@', 'a')]);
+    }
+
+    #[test]
+    fn test_custom_substitutions() {
+        let dictionary = Dictionary::from_words(["hello"]);
+        assert!(!dictionary.occurs_in("#e770"));
+        let custom = dictionary
+            .clone()
+            .with_substitutions(SubstitutionTable::default().with_substitution('#', "h"));
+        assert!(custom.occurs_in("#e770"));
+        assert!(!dictionary.with_substitutions(SubstitutionTable::new()).occurs_in("he770"));
+    }
+
+    #[test]
+    fn test_layered_dictionary() {
+        let company = Dictionary::from_words(["Acme", " ", "Rocket"]);
+        let dictionary = Dictionary::builtin().unwrap().with_layer(company);
+        assert_eq!(dictionary.len(), common_words().unwrap().words.len() + 2);
+        assert!(dictionary.occurs_in("Acme2024!"));
+        assert!(!Dictionary::builtin().unwrap().occurs_in("Acme2024!"));
+        assert!(dictionary.contains("ROCKET"));
+
+        let hits = dictionary.find("xAcme");
+        assert!(hits.contains(&DictionaryHit {
+            word: String::from("acme"),
+            rank: 1,
+            start: 1,
+            end: 5,
+            reversed: false,
+            l33t_substitutions: Vec::new(),
+            user_input: false,
+            builtin: false,
+        }));
+        assert!(hits.windows(2).all(|pair| pair[0].end <= pair[1].end));
+    }
+
+    #[test]
+    fn test_dictionary_with_user_inputs() {
+        let dictionary = Dictionary::new().with_user_inputs(["jane.doe@acme.com", "jd"]);
+        let words = &dictionary.layers[0].words;
+        assert_eq!(words, &vec!["jane.doe@acme.com", "jane", "doe", "acme", "com", "jd"]);
+        assert!(dictionary.occurs_in("acme2024"));
+        assert!(!Dictionary::new().occurs_in("acme2024"));
+        assert!(Dictionary::new().is_empty());
+    }
+
+    #[test]
+    fn test_dictionary_from_bytes_and_file() {
+        let dictionary = Dictionary::from_bytes(b"1111\tapple\n1112\tpie\xff\n");
+        assert!(dictionary.contains("APPLE"));
+        assert!(dictionary.contains("pie\u{FFFD}"));
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(PASSPHRASE_WORDS_FILE);
+        assert_eq!(Dictionary::from_file(path).unwrap().len(), 1296);
+        assert!(matches!(
+            Dictionary::from_file("missing.txt"),
+            Err(SecurepassError::DictionaryIo { .. })
+        ));
+    }
+
+    #[test]
+    fn test_use_missing_dictionary_file() {
+        let error = use_dictionary_file("missing.txt").unwrap_err();
+        assert!(matches!(error, SecurepassError::DictionaryIo { .. }));
+        assert!(std::error::Error::source(&error).is_some());
+        assert!(common_words().is_ok());
+    }
+
+    #[test]
+    fn test_read_bundled_word_list() {
+        let words = read_bundled_word_list(COMMON_WORDS_FILE).unwrap();
+        assert_eq!(words.words, common_words().unwrap().words);
+        assert!(read_bundled_word_list(PASSPHRASE_WORDS_FILE).is_ok());
+        assert_eq!(read_bundled_word_list(COMMON_PINS_FILE).unwrap().words, common_pins().unwrap().words);
+    }
+}
@@ -0,0 +1,84 @@
+//! Error type shared by every fallible operation of the crate.
+
+use crate::PatternError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Enum representing the reasons an operation of the crate can fail.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SecurepassError {
+    /// The requested password length is below the accepted minimum.
+    TooShort {
+        /// Requested length.
+        length: usize,
+        /// Minimum accepted length.
+        min_length: usize,
+    },
+    /// The requested passphrase has fewer words than the accepted minimum.
+    TooFewWords {
+        /// Requested number of words.
+        word_count: usize,
+        /// Minimum accepted number of words.
+        min_word_count: usize,
+    },
+    /// The requested PIN length is outside the accepted range.
+    InvalidPinLength {
+        /// Requested length.
+        length: usize,
+        /// Minimum accepted length.
+        min_length: usize,
+        /// Maximum accepted length.
+        max_length: usize,
+    },
+    /// The character set to draw characters from is empty.
+    EmptyCharset,
+    /// The requested options cannot be satisfied at the same time.
+    UnsatisfiableRequirements(String),
+    /// A dictionary or word list could not be read.
+    DictionaryIo {
+        /// Path of the file that failed to load.
+        path: PathBuf,
+        /// Underlying I/O error.
+        source: io::Error,
+    },
+    /// A password policy is inconsistent.
+    InvalidPolicy(String),
+    /// A generation pattern is malformed.
+    InvalidPattern(PatternError),
+}
+
+impl fmt::Display for SecurepassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { min_length, .. } => {
+                write!(f, "Password length less than {} is considered weak.", min_length)
+            }
+            Self::TooFewWords { min_word_count, .. } => {
+                write!(f, "Passphrase with less than {} words is considered weak.", min_word_count)
+            }
+            Self::InvalidPinLength { min_length, max_length, .. } => {
+                write!(f, "PIN length must be between {} and {} digits.", min_length, max_length)
+            }
+            Self::EmptyCharset => write!(f, "Character set is empty."),
+            Self::UnsatisfiableRequirements(reason) => {
+                write!(f, "Requirements cannot be satisfied: {}", reason)
+            }
+            Self::DictionaryIo { path, .. } => write!(f, "Cannot read file {}.", path.display()),
+            Self::InvalidPolicy(reason) => write!(f, "Invalid password policy: {}", reason),
+            Self::InvalidPattern(error) => write!(f, "Invalid pattern: {}", error),
+        }
+    }
+}
+
+impl Error for SecurepassError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::DictionaryIo { source, .. } => Some(source),
+            Self::InvalidPattern(error) => Some(error),
+            _ => None,
+        }
+    }
+}
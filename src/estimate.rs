@@ -0,0 +1,789 @@
+//! Pattern-matching strength estimation in the spirit of zxcvbn.
+//!
+//! The password is scanned by several matchers (dictionary words, keyboard
+//! walks, sequences, repeats and dates) and then split into the sequence of
+//! matches that needs the fewest guesses to be found by an attacker.
+
+use crate::dictionary::Dictionary;
+use crate::{normalize, Normalization, PasswordStrength, SecurepassError};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of guesses a sequence has to save before it grows by one more match.
+const MIN_GUESSES_BEFORE_GROWING_SEQUENCE: f64 = 10000.0;
+const BRUTEFORCE_CARDINALITY: f64 = 10.0;
+const MIN_SUBMATCH_GUESSES_SINGLE_CHAR: f64 = 10.0;
+const MIN_SUBMATCH_GUESSES_MULTI_CHAR: f64 = 50.0;
+const MIN_YEAR_SPACE: f64 = 20.0;
+const MAX_SEQUENCE_DELTA: i64 = 5;
+const DATE_SEPARATORS: &str = "/\\_.- ";
+
+/// Rows of a QWERTY keyboard as (unshifted keys, shifted keys, horizontal offset).
+const KEYBOARD_ROWS: [(&str, &str, f64); 4] = [
+    ("`1234567890-=", "~!@#$%^&*()_+", 0.0),
+    ("qwertyuiop[]\\", "QWERTYUIOP{}|", 1.5),
+    ("asdfghjkl;'", "ASDFGHJKL:\"", 1.75),
+    ("zxcvbnm,./", "ZXCVBNM<>?", 2.25),
+];
+
+/// Enum representing the kind of pattern a segment of a password matches.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchPattern {
+    /// A word from the common words dictionary.
+    Dictionary {
+        /// The dictionary word that was matched.
+        word: String,
+        /// Rank of the word in the dictionary, starting from 1.
+        rank: usize,
+        /// Whether the word appears reversed in the password.
+        reversed: bool,
+        /// Substitutions used, as (substituted character, original letter).
+        l33t_substitutions: Vec<(char, char)>,
+        /// Whether the word is an input about the user, like their name or
+        /// email address, rather than a common word.
+        user_input: bool,
+        /// Whether the word comes from the built-in common words rather than
+        /// a layer added to the dictionary.
+        builtin: bool,
+    },
+    /// A walk over adjacent keys of a QWERTY keyboard, like "qwerty" or "zaq1".
+    Spatial {
+        /// Number of direction changes during the walk.
+        turns: usize,
+        /// Number of characters typed with shift.
+        shifted_count: usize,
+    },
+    /// Characters with a constant step between them, like "abcd" or "9753".
+    Sequence {
+        /// Whether the characters are ascending.
+        ascending: bool,
+    },
+    /// A base string repeated several times, like "aaa" or "abcabc".
+    Repeat {
+        /// The repeated part.
+        base_token: String,
+        /// How many times the base is repeated.
+        repeat_count: usize,
+    },
+    /// A year, like "1990", or a full date, like "13.05.1990" or "130590".
+    Date {
+        /// Year of the date.
+        year: i32,
+        /// Month of the date, if the match is a full date.
+        month: Option<u32>,
+        /// Day of the date, if the match is a full date.
+        day: Option<u32>,
+        /// Separator between date parts, if any.
+        separator: Option<char>,
+    },
+    /// Characters not covered by any other pattern.
+    Bruteforce,
+}
+
+/// Structure representing a segment of a password matched by a pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordMatch {
+    /// Pattern the segment matches.
+    pub pattern: MatchPattern,
+    /// Index of the first character of the segment, in the password
+    /// normalized to NFC.
+    pub start: usize,
+    /// Index one past the last character of the segment, in the password
+    /// normalized to NFC.
+    pub end: usize,
+    /// The matched segment of the password.
+    pub token: String,
+    /// Estimated number of guesses needed to find the segment.
+    pub guesses: f64,
+}
+
+/// Structure representing the result of a pattern-matching strength estimate.
+#[derive(Debug, Clone)]
+pub struct PasswordEstimate {
+    /// Estimated number of guesses needed to find the whole password.
+    pub guesses: f64,
+    /// Base 10 logarithm of `guesses`.
+    pub guesses_log10: f64,
+    /// Matches covering the password in the decomposition needing the fewest guesses.
+    pub sequence: Vec<PasswordMatch>,
+    /// Coarse strength derived from `guesses`.
+    pub strength: PasswordStrength,
+}
+
+/// Estimates the strength of a password by matching known patterns.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with a `PasswordEstimate`, or an `Err` if the
+/// common words dictionary cannot be read.
+pub fn estimate_password_strength(password: &str) -> Result<PasswordEstimate, SecurepassError> {
+    Ok(estimate_password_strength_with_dictionary(password, &Dictionary::builtin()?))
+}
+
+/// Estimates the strength of a password by matching known patterns, looking
+/// up words in the given dictionary.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password.
+/// * `dictionary` - The dictionary of guessable words, e.g. the built-in
+///   common words with a layer of company names.
+///
+/// # Returns
+///
+/// A `PasswordEstimate` for the password.
+pub fn estimate_password_strength_with_dictionary(password: &str, dictionary: &Dictionary) -> PasswordEstimate {
+    let chars: Vec<char> = normalize(password, Normalization::Nfc).chars().collect();
+
+    estimate(&chars, dictionary)
+}
+
+/// Finds all matches in a password and picks the cheapest decomposition.
+///
+/// # Arguments
+///
+/// * `chars` - The characters of the password.
+/// * `dictionary` - Dictionary of guessable words.
+///
+/// # Returns
+///
+/// A `PasswordEstimate` for the password.
+fn estimate(chars: &[char], dictionary: &Dictionary) -> PasswordEstimate {
+    let mut matches = Vec::new();
+    matches.extend(dictionary_matches(chars, dictionary));
+    matches.extend(spatial_matches(chars));
+    matches.extend(sequence_matches(chars));
+    matches.extend(repeat_matches(chars, dictionary));
+    matches.extend(date_matches(chars));
+
+    most_guessable_match_sequence(chars, matches)
+}
+
+/// Finds dictionary words in a password, ignoring case, reversal and l33t
+/// substitutions.
+///
+/// A word found backwards needs twice as many guesses, and one found behind
+/// l33t substitutions is multiplied by the number of ways its substituted
+/// characters could have been chosen.
+fn dictionary_matches(chars: &[char], dictionary: &Dictionary) -> Vec<PasswordMatch> {
+    dictionary
+        .find_chars(chars)
+        .into_iter()
+        .map(|hit| {
+            let token: String = chars[hit.start..hit.end].iter().collect();
+            let mut guesses = hit.rank as f64 * uppercase_variations(&token);
+            if hit.reversed {
+                guesses *= 2.0;
+            }
+            if !hit.l33t_substitutions.is_empty() {
+                guesses *= l33t_variations(&token, &hit.l33t_substitutions);
+            }
+            PasswordMatch {
+                pattern: MatchPattern::Dictionary {
+                    word: hit.word,
+                    rank: hit.rank,
+                    reversed: hit.reversed,
+                    l33t_substitutions: hit.l33t_substitutions,
+                    user_input: hit.user_input,
+                    builtin: hit.builtin,
+                },
+                start: hit.start,
+                end: hit.end,
+                token,
+                guesses,
+            }
+        })
+        .collect()
+}
+
+/// Finds walks over adjacent keys of a QWERTY keyboard.
+fn spatial_matches(chars: &[char]) -> Vec<PasswordMatch> {
+    let mut matches = Vec::new();
+    let mut start = 0;
+
+    while start + 2 < chars.len() {
+        let mut end = start + 1;
+        let mut turns = 0;
+        let mut last_direction = None;
+
+        while end < chars.len() {
+            match key_direction(chars[end - 1], chars[end]) {
+                Some(direction) => {
+                    if last_direction != Some(direction) {
+                        turns += 1;
+                        last_direction = Some(direction);
+                    }
+                    end += 1;
+                }
+                None => break,
+            }
+        }
+
+        if end - start >= 3 {
+            let token: String = chars[start..end].iter().collect();
+            let shifted_count = chars[start..end]
+                .iter()
+                .filter(|c| matches!(keyboard_position(**c), Some((_, _, true))))
+                .count();
+            matches.push(PasswordMatch {
+                pattern: MatchPattern::Spatial { turns, shifted_count },
+                start,
+                end,
+                guesses: spatial_guesses(end - start, turns, shifted_count),
+                token,
+            });
+            start = end - 1;
+        } else {
+            start += 1;
+        }
+    }
+
+    matches
+}
+
+/// Finds the keyboard row, horizontal position and shift state of a key.
+fn keyboard_position(c: char) -> Option<(usize, f64, bool)> {
+    KEYBOARD_ROWS
+        .iter()
+        .enumerate()
+        .find_map(|(row, (unshifted, shifted, offset))| {
+            if let Some(column) = unshifted.chars().position(|key| key == c) {
+                Some((row, offset + column as f64, false))
+            } else {
+                shifted
+                    .chars()
+                    .position(|key| key == c)
+                    .map(|column| (row, offset + column as f64, true))
+            }
+        })
+}
+
+/// Returns the direction from one key to an adjacent key, or `None` if the
+/// keys are not adjacent.
+fn key_direction(from: char, to: char) -> Option<(i64, i64)> {
+    let (from_row, from_x, _) = keyboard_position(from)?;
+    let (to_row, to_x, _) = keyboard_position(to)?;
+    let row_delta = to_row as i64 - from_row as i64;
+    let x_delta = to_x - from_x;
+
+    let adjacent = match row_delta {
+        0 => x_delta.abs() == 1.0,
+        -1 | 1 => x_delta.abs() <= 1.0,
+        _ => false,
+    };
+
+    adjacent.then_some((row_delta, x_delta.signum() as i64))
+}
+
+/// Estimates guesses for a keyboard walk of the given shape.
+fn spatial_guesses(length: usize, turns: usize, shifted_count: usize) -> f64 {
+    let keys: Vec<char> = KEYBOARD_ROWS.iter().flat_map(|(unshifted, _, _)| unshifted.chars()).collect();
+    let starting_positions = keys.len() as f64;
+    let neighbours: usize = keys
+        .iter()
+        .map(|from| keys.iter().filter(|to| key_direction(*from, **to).is_some()).count())
+        .sum();
+    let average_degree = neighbours as f64 / starting_positions;
+    let mut guesses = 0.0;
+
+    for i in 2..=length {
+        for j in 1..=turns.min(i - 1) {
+            guesses += binomial(i - 1, j - 1) * starting_positions * average_degree.powi(j as i32);
+        }
+    }
+
+    let unshifted_count = length - shifted_count;
+    if shifted_count > 0 {
+        if unshifted_count == 0 {
+            guesses *= 2.0;
+        } else {
+            guesses *= (1..=shifted_count.min(unshifted_count))
+                .map(|i| binomial(shifted_count + unshifted_count, i))
+                .sum::<f64>();
+        }
+    }
+
+    guesses
+}
+
+/// Finds runs of characters with a constant step, like "abcd" or "9753".
+fn sequence_matches(chars: &[char]) -> Vec<PasswordMatch> {
+    let mut matches = Vec::new();
+    let mut start = 0;
+
+    while start + 2 < chars.len() {
+        let delta = chars[start + 1] as i64 - chars[start] as i64;
+        if delta == 0 || delta.abs() > MAX_SEQUENCE_DELTA {
+            start += 1;
+            continue;
+        }
+
+        let mut end = start + 2;
+        while end < chars.len() && chars[end] as i64 - chars[end - 1] as i64 == delta {
+            end += 1;
+        }
+
+        if end - start >= 3 {
+            let token: String = chars[start..end].iter().collect();
+            let first = chars[start];
+            let mut base = if "aAzZ019".contains(first) {
+                4.0
+            } else if first.is_ascii_digit() {
+                10.0
+            } else {
+                26.0
+            };
+            if delta < 0 {
+                base *= 2.0;
+            }
+            matches.push(PasswordMatch {
+                pattern: MatchPattern::Sequence { ascending: delta > 0 },
+                start,
+                end,
+                guesses: base * (end - start) as f64,
+                token,
+            });
+        }
+        start = end - 1;
+    }
+
+    matches
+}
+
+/// Finds a base string repeated several times, like "aaa" or "abcabc".
+fn repeat_matches(chars: &[char], dictionary: &Dictionary) -> Vec<PasswordMatch> {
+    let mut matches = Vec::new();
+    let mut start = 0;
+
+    while start < chars.len() {
+        let mut best: Option<(usize, usize)> = None;
+
+        for base_length in 1..=(chars.len() - start) / 2 {
+            let base = &chars[start..start + base_length];
+            let mut repeat_count = 1;
+            while start + (repeat_count + 1) * base_length <= chars.len()
+                && &chars[start + repeat_count * base_length..start + (repeat_count + 1) * base_length] == base
+            {
+                repeat_count += 1;
+            }
+            let covered = repeat_count * base_length;
+            if repeat_count >= 2 && best.is_none_or(|(length, count)| covered > length * count) {
+                best = Some((base_length, repeat_count));
+            }
+        }
+
+        match best {
+            Some((base_length, repeat_count)) => {
+                let end = start + base_length * repeat_count;
+                let base_chars = &chars[start..start + base_length];
+                let base_guesses = estimate(base_chars, dictionary).guesses;
+                matches.push(PasswordMatch {
+                    pattern: MatchPattern::Repeat {
+                        base_token: base_chars.iter().collect(),
+                        repeat_count,
+                    },
+                    start,
+                    end,
+                    token: chars[start..end].iter().collect(),
+                    guesses: base_guesses * repeat_count as f64,
+                });
+                start = end;
+            }
+            None => start += 1,
+        }
+    }
+
+    matches
+}
+
+/// Finds years and dates, with or without separators.
+fn date_matches(chars: &[char]) -> Vec<PasswordMatch> {
+    let mut matches = Vec::new();
+
+    for start in 0..chars.len() {
+        for end in start + 4..=chars.len().min(start + 10) {
+            let token: String = chars[start..end].iter().collect();
+            if let Some((year, month, day, separator)) = parse_date(&token) {
+                let year_space = ((year - reference_year()).abs() as f64).max(MIN_YEAR_SPACE);
+                let mut guesses = if month.is_some() { year_space * 365.0 } else { year_space };
+                if separator.is_some() {
+                    guesses *= 4.0;
+                }
+                matches.push(PasswordMatch {
+                    pattern: MatchPattern::Date { year, month, day, separator },
+                    start,
+                    end,
+                    token,
+                    guesses,
+                });
+            }
+        }
+    }
+
+    matches
+}
+
+/// Parses a token as a recent year or as a date in day-month-year,
+/// month-day-year or year-month-day order.
+#[allow(clippy::type_complexity)]
+fn parse_date(token: &str) -> Option<(i32, Option<u32>, Option<u32>, Option<char>)> {
+    if token.len() == 4 && token.chars().all(|c| c.is_ascii_digit()) {
+        let year: i32 = token.parse().ok()?;
+        if (1900..=2050).contains(&year) {
+            return Some((year, None, None, None));
+        }
+    }
+
+    let separator = token.chars().find(|c| DATE_SEPARATORS.contains(*c));
+    let candidates: Vec<[&str; 3]> = match separator {
+        Some(separator) => {
+            let parts: Vec<&str> = token.split(separator).collect();
+            if parts.len() != 3 || parts.iter().any(|part| part.is_empty() || !part.chars().all(|c| c.is_ascii_digit())) {
+                return None;
+            }
+            vec![[parts[0], parts[1], parts[2]]]
+        }
+        None => {
+            if !token.chars().all(|c| c.is_ascii_digit()) || token.len() > 8 {
+                return None;
+            }
+            (1..token.len() - 1)
+                .flat_map(|first| (first + 1..token.len()).map(move |second| (first, second)))
+                .map(|(first, second)| [&token[..first], &token[first..second], &token[second..]])
+                .collect()
+        }
+    };
+
+    candidates
+        .into_iter()
+        .flat_map(|[first, second, third]| {
+            [(third, second, first), (third, first, second), (first, second, third)]
+        })
+        .filter_map(|(year, month, day)| {
+            if !(year.len() == 2 || year.len() == 4) || month.len() > 2 || day.len() > 2 {
+                return None;
+            }
+            let month: u32 = month.parse().ok()?;
+            let day: u32 = day.parse().ok()?;
+            let mut year: i32 = year.parse().ok()?;
+            if year < 100 {
+                year += if year > 50 { 1900 } else { 2000 };
+            }
+            let valid = (1..=12).contains(&month) && (1..=31).contains(&day) && (1000..=2050).contains(&year);
+            valid.then_some((year, Some(month), Some(day), separator))
+        })
+        .min_by_key(|(year, ..)| (year - reference_year()).abs())
+}
+
+/// Returns the current year, used as a reference for date guesses.
+fn reference_year() -> i32 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    1970 + (seconds / 31_556_952) as i32
+}
+
+/// Finds the decomposition of a password into matches needing the fewest
+/// guesses, filling uncovered characters with bruteforce matches.
+fn most_guessable_match_sequence(chars: &[char], matches: Vec<PasswordMatch>) -> PasswordEstimate {
+    let length = chars.len();
+    if length == 0 {
+        return PasswordEstimate {
+            guesses: 1.0,
+            guesses_log10: 0.0,
+            sequence: Vec::new(),
+            strength: PasswordStrength::Weak,
+        };
+    }
+
+    let mut by_end: Vec<Vec<PasswordMatch>> = vec![Vec::new(); length];
+    for mut found in matches {
+        found.guesses = found.guesses.max(min_submatch_guesses(&found));
+        by_end[found.end - 1].push(found);
+    }
+
+    // optimal[k][l] is the best sequence of l matches covering chars[..=k],
+    // stored as (total guesses, product of match guesses, last match).
+    let mut optimal: Vec<HashMap<usize, (f64, f64, PasswordMatch)>> = vec![HashMap::new(); length];
+
+    for k in 0..length {
+        for found in &by_end[k] {
+            for (sequence_length, product) in previous_steps(&optimal, found.start, false) {
+                update_optimal(&mut optimal[k], found.clone(), sequence_length + 1, product);
+            }
+        }
+        for start in 0..=k {
+            let bruteforce = bruteforce_match(chars, start, k + 1);
+            for (sequence_length, product) in previous_steps(&optimal, start, true) {
+                update_optimal(&mut optimal[k], bruteforce.clone(), sequence_length + 1, product);
+            }
+        }
+    }
+
+    let (mut sequence_length, (guesses, ..)) = optimal[length - 1]
+        .iter()
+        .min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+        .map(|(l, step)| (*l, step.clone()))
+        .expect("bruteforce always covers the password");
+
+    let mut sequence = Vec::with_capacity(sequence_length);
+    let mut k = length - 1;
+    loop {
+        let found = optimal[k][&sequence_length].2.clone();
+        let start = found.start;
+        sequence.push(found);
+        if start == 0 {
+            break;
+        }
+        k = start - 1;
+        sequence_length -= 1;
+    }
+    sequence.reverse();
+
+    let guesses_log10 = guesses.log10();
+    PasswordEstimate {
+        guesses,
+        guesses_log10,
+        sequence,
+        strength: strength_from_guesses_log10(guesses_log10),
+    }
+}
+
+/// Lists the (sequence length, product of guesses) pairs a match starting at
+/// `start` can extend. Bruteforce matches never extend another bruteforce match.
+fn previous_steps(
+    optimal: &[HashMap<usize, (f64, f64, PasswordMatch)>],
+    start: usize,
+    is_bruteforce: bool,
+) -> Vec<(usize, f64)> {
+    if start == 0 {
+        return vec![(0, 1.0)];
+    }
+
+    optimal[start - 1]
+        .iter()
+        .filter(|(_, (_, _, last))| !(is_bruteforce && last.pattern == MatchPattern::Bruteforce))
+        .map(|(sequence_length, (_, product, _))| (*sequence_length, *product))
+        .collect()
+}
+
+/// Records a candidate sequence ending with `found` if no shorter or equally
+/// long sequence ending at the same position needs fewer guesses.
+fn update_optimal(
+    steps: &mut HashMap<usize, (f64, f64, PasswordMatch)>,
+    found: PasswordMatch,
+    sequence_length: usize,
+    previous_product: f64,
+) {
+    let product = previous_product * found.guesses;
+    let guesses = factorial(sequence_length) * product
+        + MIN_GUESSES_BEFORE_GROWING_SEQUENCE.powi(sequence_length as i32 - 1);
+
+    let dominated = steps
+        .iter()
+        .any(|(other_length, (other_guesses, ..))| *other_length <= sequence_length && *other_guesses <= guesses);
+    if !dominated {
+        steps.insert(sequence_length, (guesses, product, found));
+    }
+}
+
+/// Builds a bruteforce match for `chars[start..end]`.
+fn bruteforce_match(chars: &[char], start: usize, end: usize) -> PasswordMatch {
+    let length = end - start;
+    let min_guesses = if length == 1 {
+        MIN_SUBMATCH_GUESSES_SINGLE_CHAR
+    } else {
+        MIN_SUBMATCH_GUESSES_MULTI_CHAR
+    };
+
+    PasswordMatch {
+        pattern: MatchPattern::Bruteforce,
+        start,
+        end,
+        token: chars[start..end].iter().collect(),
+        guesses: BRUTEFORCE_CARDINALITY.powi(length as i32).max(min_guesses + 1.0),
+    }
+}
+
+/// Returns the lowest number of guesses a non-bruteforce match may report.
+fn min_submatch_guesses(found: &PasswordMatch) -> f64 {
+    if found.end - found.start == 1 {
+        MIN_SUBMATCH_GUESSES_SINGLE_CHAR
+    } else {
+        MIN_SUBMATCH_GUESSES_MULTI_CHAR
+    }
+}
+
+/// Maps the number of guesses to the coarse `PasswordStrength`.
+fn strength_from_guesses_log10(guesses_log10: f64) -> PasswordStrength {
+    if guesses_log10 < 8.0 {
+        PasswordStrength::Weak
+    } else if guesses_log10 < 10.0 {
+        PasswordStrength::Medium
+    } else {
+        PasswordStrength::Strong
+    }
+}
+
+/// Counts the ways a word could have been capitalized to give `token`.
+fn uppercase_variations(token: &str) -> f64 {
+    let upper = token.chars().filter(|c| c.is_uppercase()).count();
+    let lower = token.chars().filter(|c| c.is_lowercase()).count();
+    if upper == 0 {
+        return 1.0;
+    }
+
+    let first_upper = token.chars().next().is_some_and(char::is_uppercase);
+    let last_upper = token.chars().last().is_some_and(char::is_uppercase);
+    if lower == 0 || (upper == 1 && (first_upper || last_upper)) {
+        return 2.0;
+    }
+
+    (1..=upper.min(lower)).map(|i| binomial(upper + lower, i)).sum()
+}
+
+/// Counts the ways the given l33t substitutions could have been applied to a word.
+fn l33t_variations(token: &str, substitutions: &[(char, char)]) -> f64 {
+    let lower = token.to_lowercase();
+
+    substitutions
+        .iter()
+        .map(|(l33t, letter)| {
+            let substituted = lower.chars().filter(|c| c == l33t).count();
+            let unsubstituted = lower.chars().filter(|c| c == letter).count();
+            if substituted == 0 || unsubstituted == 0 {
+                2.0
+            } else {
+                (1..=substituted.min(unsubstituted))
+                    .map(|i| binomial(substituted + unsubstituted, i))
+                    .sum()
+            }
+        })
+        .product()
+}
+
+/// Calculates the binomial coefficient `n` choose `k`.
+fn binomial(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+
+    (1..=k).fold(1.0, |result, i| result * (n + 1 - i) as f64 / i as f64)
+}
+
+/// Calculates `n!`.
+fn factorial(n: usize) -> f64 {
+    (2..=n).fold(1.0, |result, i| result * i as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(password: &str) -> Vec<char> {
+        password.chars().collect()
+    }
+
+    fn has_dictionary_match(estimate: &PasswordEstimate, expected: &str, expected_reversed: bool) -> bool {
+        estimate.sequence.iter().any(|found| {
+            matches!(&found.pattern, MatchPattern::Dictionary { word, reversed, .. }
+                if word == expected && *reversed == expected_reversed)
+        })
+    }
+
+    #[test]
+    fn test_estimate_with_company_dictionary() {
+        let dictionary = Dictionary::builtin().unwrap().with_layer(Dictionary::from_words(["acmecorp"]));
+        let generic = estimate_password_strength("Acmecorp2024!").unwrap();
+        let company = estimate_password_strength_with_dictionary("Acmecorp2024!", &dictionary);
+        assert!(has_dictionary_match(&company, "acmecorp", false));
+        assert!(company.guesses < generic.guesses);
+    }
+
+    #[test]
+    fn test_estimate_common_password() {
+        let estimate = estimate_password_strength("Password1!").unwrap();
+        assert!(matches!(estimate.strength, PasswordStrength::Weak));
+        assert!(has_dictionary_match(&estimate, "password", false));
+    }
+
+    #[test]
+    fn test_estimate_reversed_and_l33t_words() {
+        let reversed = estimate_password_strength("drowssap").unwrap();
+        assert!(has_dictionary_match(&reversed, "password", true));
+
+        let l33t = estimate_password_strength("p@ssw0rd").unwrap();
+        let substitutions = l33t.sequence.iter().find_map(|found| match &found.pattern {
+            MatchPattern::Dictionary { word, l33t_substitutions, .. } if word == "password" => {
+                Some(l33t_substitutions.clone())
+            }
+            _ => None,
+        });
+        assert_eq!(substitutions, Some(vec![('0', 'o'), ('@', 'a')]));
+        assert!(matches!(l33t.strength, PasswordStrength::Weak));
+    }
+
+    #[test]
+    fn test_spatial_matches() {
+        let found = spatial_matches(&chars("xasdfgh9"));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].token, "asdfgh");
+        assert_eq!(found[0].pattern, MatchPattern::Spatial { turns: 1, shifted_count: 0 });
+
+        let walk = spatial_matches(&chars("zaQ!"));
+        assert_eq!(walk[0].pattern, MatchPattern::Spatial { turns: 1, shifted_count: 2 });
+    }
+
+    #[test]
+    fn test_sequence_matches() {
+        let found = sequence_matches(&chars("xabcdefx9753"));
+        let tokens: Vec<&str> = found.iter().map(|found| found.token.as_str()).collect();
+        assert_eq!(tokens, vec!["abcdef", "9753"]);
+        assert_eq!(found[1].pattern, MatchPattern::Sequence { ascending: false });
+    }
+
+    #[test]
+    fn test_repeat_matches() {
+        let dictionary = Dictionary::new();
+        let found = repeat_matches(&chars("abcabcabcz"), &dictionary);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].pattern,
+            MatchPattern::Repeat { base_token: "abc".to_string(), repeat_count: 3 }
+        );
+    }
+
+    #[test]
+    fn test_date_matches() {
+        let found = date_matches(&chars("x13.05.1990"));
+        assert!(found.iter().any(|found| found.token == "13.05.1990"
+            && found.pattern
+                == MatchPattern::Date { year: 1990, month: Some(5), day: Some(13), separator: Some('.') }));
+
+        let year = date_matches(&chars("1987"));
+        assert_eq!(
+            year[0].pattern,
+            MatchPattern::Date { year: 1987, month: None, day: None, separator: None }
+        );
+    }
+
+    #[test]
+    fn test_estimate_random_password() {
+        let estimate = estimate_password_strength("!QEa4Kta2}wg1").unwrap();
+        assert!(matches!(estimate.strength, PasswordStrength::Strong));
+        let covered: String = estimate.sequence.iter().map(|found| found.token.as_str()).collect();
+        assert_eq!(covered, "!QEa4Kta2}wg1");
+    }
+
+    #[test]
+    fn test_estimate_empty_password() {
+        let estimate = estimate_password_strength("").unwrap();
+        assert_eq!(estimate.guesses, 1.0);
+        assert!(estimate.sequence.is_empty());
+    }
+}
@@ -0,0 +1,396 @@
+//! Warnings and suggestions explaining why a password is weak.
+
+use crate::{
+    calculate_entropy, check_password_specification, check_password_strength_with_dictionary,
+    estimate_password_strength_with_dictionary, normalize, Dictionary, MatchPattern, Normalization, PasswordEstimate,
+    PasswordSpecification, PasswordStrength, SecurepassError, MIN_PASSWORD_LENGTH,
+};
+use std::fmt;
+
+/// Rank below which a dictionary word counts as one of the most common passwords.
+const TOP_PASSWORDS_RANK: usize = 100;
+/// Entropy below which a password is always considered weak.
+const LOW_ENTROPY: f64 = 40.0;
+
+/// Enum representing a problem found in a password.
+///
+/// Use [`FeedbackWarning::message`] for the English text, or match on the
+/// variant to provide a localized one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedbackWarning {
+    /// The whole password is one of the 100 most common passwords.
+    TopHundredPassword,
+    /// The whole password is a common password.
+    CommonPassword,
+    /// The password contains a common word.
+    ContainsCommonWord,
+    /// The password contains personal information given as user inputs,
+    /// like the user's name or email address.
+    PersonalInformation,
+    /// The password contains a common word written backwards.
+    ReversedWord,
+    /// The password relies on predictable substitutions like '@' for 'a'.
+    PredictableSubstitutions,
+    /// The password contains a keyboard pattern like "qwerty".
+    KeyboardPattern,
+    /// The password contains a sequence like "abc" or "6543".
+    Sequence,
+    /// The password contains repeated characters like "aaa" or "abcabc".
+    Repeat,
+    /// The password contains a year or a date.
+    Date,
+    /// The password lacks at least one character class.
+    MissingCharacterClass,
+    /// The password is shorter than recommended.
+    TooShort,
+    /// The password has low entropy.
+    LowEntropy,
+}
+
+impl FeedbackWarning {
+    /// Returns the English message of the warning.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::TopHundredPassword => "This is a top-100 common password.",
+            Self::CommonPassword => "This is a very common password.",
+            Self::ContainsCommonWord => "Common words are easy to guess.",
+            Self::PersonalInformation => "This password contains your personal information.",
+            Self::ReversedWord => "Reversed words aren't much harder to guess.",
+            Self::PredictableSubstitutions => "Predictable substitutions like '@' instead of 'a' don't help very much.",
+            Self::KeyboardPattern => "Keyboard patterns like qwerty are easy to guess.",
+            Self::Sequence => "Sequences like abc or 6543 are easy to guess.",
+            Self::Repeat => "Repeats like aaa or abcabc are easy to guess.",
+            Self::Date => "Dates and years are often easy to guess.",
+            Self::MissingCharacterClass => "Passwords using few character classes are easier to guess.",
+            Self::TooShort => "Short passwords are easy to guess.",
+            Self::LowEntropy => "This password has too little randomness.",
+        }
+    }
+}
+
+impl fmt::Display for FeedbackWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// Enum representing a concrete step that makes a password stronger.
+///
+/// Use [`FeedbackSuggestion::message`] for the English text, or match on the
+/// variant to provide a localized one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedbackSuggestion {
+    /// Make the password longer.
+    IncreaseLength,
+    /// Add a few uncommon words.
+    AddUncommonWords,
+    /// Add a lowercase letter.
+    AddLowercase,
+    /// Add an uppercase letter.
+    AddUppercase,
+    /// Add a number.
+    AddNumber,
+    /// Add a special character.
+    AddSpecialChar,
+    /// Avoid common passwords and words.
+    AvoidCommonWords,
+    /// Avoid personal information like names and email addresses.
+    AvoidPersonalInformation,
+    /// Avoid reversed spellings of common words.
+    AvoidReversedWords,
+    /// Avoid predictable substitutions.
+    AvoidPredictableSubstitutions,
+    /// Capitalize more than the first letter.
+    AvoidPredictableCapitalization,
+    /// Avoid keyboard patterns.
+    AvoidKeyboardPatterns,
+    /// Avoid sequences.
+    AvoidSequences,
+    /// Avoid repeated words and characters.
+    AvoidRepeats,
+    /// Avoid dates and years associated with you.
+    AvoidDates,
+}
+
+impl FeedbackSuggestion {
+    /// Returns the English message of the suggestion.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::IncreaseLength => "Use a longer password.",
+            Self::AddUncommonWords => "Add another word or two. Uncommon words are better.",
+            Self::AddLowercase => "Add a lowercase letter.",
+            Self::AddUppercase => "Add an uppercase letter.",
+            Self::AddNumber => "Add a number.",
+            Self::AddSpecialChar => "Add a special character.",
+            Self::AvoidCommonWords => "Avoid common passwords and words.",
+            Self::AvoidPersonalInformation => "Avoid your name, username and email address.",
+            Self::AvoidReversedWords => "Avoid reversed spellings of common words.",
+            Self::AvoidPredictableSubstitutions => "Avoid predictable substitutions like '@' instead of 'a'.",
+            Self::AvoidPredictableCapitalization => "Capitalize more than the first letter.",
+            Self::AvoidKeyboardPatterns => "Avoid keyboard patterns.",
+            Self::AvoidSequences => "Avoid sequences.",
+            Self::AvoidRepeats => "Avoid repeated words and characters.",
+            Self::AvoidDates => "Avoid dates and years that are associated with you.",
+        }
+    }
+}
+
+impl fmt::Display for FeedbackSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// Structure representing a detailed strength check of a password.
+#[derive(Debug, Clone)]
+pub struct StrengthReport {
+    /// Strength as returned by [`crate::check_password_strength`].
+    pub strength: PasswordStrength,
+    /// Entropy as returned by [`crate::calculate_entropy`].
+    pub entropy: f64,
+    /// Character classes present in the password.
+    pub specification: PasswordSpecification,
+    /// Pattern-matching estimate of the password.
+    pub estimate: PasswordEstimate,
+    /// Problems found in the password. Empty for strong passwords.
+    pub warnings: Vec<FeedbackWarning>,
+    /// Steps that make the password stronger. Empty for strong passwords.
+    pub suggestions: Vec<FeedbackSuggestion>,
+}
+
+/// Checks the strength of a password and explains the result.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with a `StrengthReport`, or an `Err` if the
+/// common words dictionary cannot be read.
+pub fn check_password_strength_report(password: &str) -> Result<StrengthReport, SecurepassError> {
+    Ok(check_password_strength_report_with_dictionary(password, &Dictionary::builtin()?))
+}
+
+/// Checks the strength of a password and explains the result, looking up
+/// words in the given dictionary.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password.
+/// * `dictionary` - The dictionary of guessable words.
+///
+/// # Returns
+///
+/// A `StrengthReport` for the password.
+pub fn check_password_strength_report_with_dictionary(password: &str, dictionary: &Dictionary) -> StrengthReport {
+    let strength = check_password_strength_with_dictionary(password, dictionary);
+    let estimate = estimate_password_strength_with_dictionary(password, dictionary);
+    let entropy = calculate_entropy(password);
+    let specification = check_password_specification(password);
+
+    let mut report = StrengthReport {
+        strength,
+        entropy,
+        specification,
+        estimate,
+        warnings: Vec::new(),
+        suggestions: Vec::new(),
+    };
+    if report.strength != PasswordStrength::Strong || report.estimate.strength != PasswordStrength::Strong {
+        add_pattern_feedback(&mut report);
+        add_composition_feedback(&mut report, &normalize(password, Normalization::Nfc));
+    }
+
+    report
+}
+
+/// Adds feedback for the patterns found by the strength estimate.
+fn add_pattern_feedback(report: &mut StrengthReport) {
+    let whole_password = report.estimate.sequence.len() == 1;
+
+    for found in report.estimate.sequence.clone() {
+        match &found.pattern {
+            MatchPattern::Dictionary {
+                rank,
+                reversed,
+                l33t_substitutions,
+                user_input,
+                builtin,
+                ..
+            } => {
+                if *user_input {
+                    add_feedback(
+                        report,
+                        FeedbackWarning::PersonalInformation,
+                        FeedbackSuggestion::AvoidPersonalInformation,
+                    );
+                } else {
+                    let warning = if *builtin && whole_password && *rank <= TOP_PASSWORDS_RANK {
+                        FeedbackWarning::TopHundredPassword
+                    } else if *builtin && whole_password {
+                        FeedbackWarning::CommonPassword
+                    } else {
+                        FeedbackWarning::ContainsCommonWord
+                    };
+                    add_feedback(report, warning, FeedbackSuggestion::AvoidCommonWords);
+                }
+                if *reversed {
+                    add_feedback(report, FeedbackWarning::ReversedWord, FeedbackSuggestion::AvoidReversedWords);
+                }
+                if !l33t_substitutions.is_empty() {
+                    add_feedback(
+                        report,
+                        FeedbackWarning::PredictableSubstitutions,
+                        FeedbackSuggestion::AvoidPredictableSubstitutions,
+                    );
+                }
+                let mut chars = found.token.chars();
+                if chars.next().is_some_and(char::is_uppercase) && !chars.any(char::is_uppercase) {
+                    add_suggestion(report, FeedbackSuggestion::AvoidPredictableCapitalization);
+                }
+            }
+            MatchPattern::Spatial { .. } => {
+                add_feedback(report, FeedbackWarning::KeyboardPattern, FeedbackSuggestion::AvoidKeyboardPatterns);
+            }
+            MatchPattern::Sequence { .. } => {
+                add_feedback(report, FeedbackWarning::Sequence, FeedbackSuggestion::AvoidSequences);
+            }
+            MatchPattern::Repeat { .. } => {
+                add_feedback(report, FeedbackWarning::Repeat, FeedbackSuggestion::AvoidRepeats);
+            }
+            MatchPattern::Date { .. } => {
+                add_feedback(report, FeedbackWarning::Date, FeedbackSuggestion::AvoidDates);
+            }
+            MatchPattern::Bruteforce => {}
+        }
+    }
+}
+
+/// Adds feedback for the length, entropy and character classes of a password.
+fn add_composition_feedback(report: &mut StrengthReport, password: &str) {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        add_feedback(report, FeedbackWarning::TooShort, FeedbackSuggestion::IncreaseLength);
+    }
+    if report.entropy < LOW_ENTROPY {
+        add_feedback(report, FeedbackWarning::LowEntropy, FeedbackSuggestion::IncreaseLength);
+    }
+
+    let specification = &report.specification;
+    let missing: Vec<FeedbackSuggestion> = [
+        (specification.has_lowercase, FeedbackSuggestion::AddLowercase),
+        (specification.has_uppercase, FeedbackSuggestion::AddUppercase),
+        (specification.has_number, FeedbackSuggestion::AddNumber),
+        (specification.has_special, FeedbackSuggestion::AddSpecialChar),
+    ]
+    .into_iter()
+    .filter(|(present, _)| !present)
+    .map(|(_, suggestion)| suggestion)
+    .collect();
+
+    if !missing.is_empty() {
+        add_warning(report, FeedbackWarning::MissingCharacterClass);
+    }
+    for suggestion in missing {
+        add_suggestion(report, suggestion);
+    }
+
+    if report.estimate.sequence.iter().any(|found| found.pattern != MatchPattern::Bruteforce) {
+        add_suggestion(report, FeedbackSuggestion::AddUncommonWords);
+    }
+}
+
+/// Adds a warning with its suggestion, skipping duplicates.
+fn add_feedback(report: &mut StrengthReport, warning: FeedbackWarning, suggestion: FeedbackSuggestion) {
+    add_warning(report, warning);
+    add_suggestion(report, suggestion);
+}
+
+/// Adds a warning, skipping duplicates.
+fn add_warning(report: &mut StrengthReport, warning: FeedbackWarning) {
+    if !report.warnings.contains(&warning) {
+        report.warnings.push(warning);
+    }
+}
+
+/// Adds a suggestion, skipping duplicates.
+fn add_suggestion(report: &mut StrengthReport, suggestion: FeedbackSuggestion) {
+    if !report.suggestions.contains(&suggestion) {
+        report.suggestions.push(suggestion);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_top_password() {
+        let report = check_password_strength_report("password").unwrap();
+        assert_eq!(report.strength, PasswordStrength::Weak);
+        assert_eq!(report.warnings[0], FeedbackWarning::TopHundredPassword);
+        assert!(report.warnings.contains(&FeedbackWarning::MissingCharacterClass));
+        assert!(report.warnings.contains(&FeedbackWarning::TooShort));
+        assert!(report.suggestions.contains(&FeedbackSuggestion::AddUppercase));
+        assert!(report.suggestions.contains(&FeedbackSuggestion::AddNumber));
+        assert!(report.suggestions.contains(&FeedbackSuggestion::AddSpecialChar));
+        assert!(!report.suggestions.contains(&FeedbackSuggestion::AddLowercase));
+    }
+
+    #[test]
+    fn test_report_patterns() {
+        let report = check_password_strength_report("P@ssw0rdabcd").unwrap();
+        assert!(report.warnings.contains(&FeedbackWarning::ContainsCommonWord));
+        assert!(report.warnings.contains(&FeedbackWarning::PredictableSubstitutions));
+        assert!(report.warnings.contains(&FeedbackWarning::Sequence));
+        assert!(report.suggestions.contains(&FeedbackSuggestion::AvoidPredictableCapitalization));
+
+        let report = check_password_strength_report("sdfghj1990").unwrap();
+        assert!(report.warnings.contains(&FeedbackWarning::KeyboardPattern));
+        assert!(report.warnings.contains(&FeedbackWarning::Date));
+    }
+
+    #[test]
+    fn test_report_strong_password() {
+        let report = check_password_strength_report("!QEa4Kta2}wg1").unwrap();
+        assert_eq!(report.strength, PasswordStrength::Strong);
+        assert!(report.warnings.is_empty());
+        assert!(report.suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_report_with_user_inputs() {
+        let dictionary = Dictionary::builtin().unwrap().with_user_inputs(["!QEa4Kta2}wg1"]);
+        let report = check_password_strength_report_with_dictionary("!QEa4Kta2}wg1", &dictionary);
+        assert_eq!(report.estimate.strength, PasswordStrength::Weak);
+        assert_eq!(report.warnings[0], FeedbackWarning::PersonalInformation);
+        assert!(!report.warnings.contains(&FeedbackWarning::TopHundredPassword));
+        assert!(report
+            .suggestions
+            .contains(&FeedbackSuggestion::AvoidPersonalInformation));
+
+        let dictionary = Dictionary::builtin()
+            .unwrap()
+            .with_user_inputs(["jane.kowalski@acme.com"]);
+        let report = check_password_strength_report_with_dictionary("kowalski!1987", &dictionary);
+        assert!(report.warnings.contains(&FeedbackWarning::PersonalInformation));
+    }
+
+    #[test]
+    fn test_report_with_company_dictionary() {
+        let dictionary = Dictionary::builtin().unwrap().with_layer(Dictionary::from_words(["acme"]));
+        let report = check_password_strength_report_with_dictionary("acme", &dictionary);
+        assert!(report.warnings.contains(&FeedbackWarning::ContainsCommonWord));
+        assert!(!report.warnings.contains(&FeedbackWarning::TopHundredPassword));
+        assert!(!report.warnings.contains(&FeedbackWarning::CommonPassword));
+
+        let report = check_password_strength_report_with_dictionary("password", &dictionary);
+        assert!(report.warnings.contains(&FeedbackWarning::TopHundredPassword));
+    }
+
+    #[test]
+    fn test_feedback_messages() {
+        assert_eq!(FeedbackWarning::TopHundredPassword.to_string(), "This is a top-100 common password.");
+        assert_eq!(FeedbackSuggestion::AvoidSequences.message(), "Avoid sequences.");
+    }
+}
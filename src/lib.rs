@@ -1,11 +1,62 @@
 //! This crate provides functionality to generate and balance passwords
 //! with various options and strengths.
 
-use rand::{thread_rng, Rng};
-use std::fs;
-use std::path::Path;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, CryptoRng, RngCore};
+
+mod aho_corasick;
+mod bulk;
+mod character_sets;
+mod charset;
+mod crack_time;
+mod dictionary;
+mod error;
+mod estimate;
+mod feedback;
+mod memorable;
+mod minimal_balance;
+mod normalization;
+mod passphrase;
+mod pattern;
+mod pin;
+mod policy;
+mod presets;
+mod pronounceable;
+mod site;
+mod substitution;
+
+pub use bulk::PasswordStream;
+pub use character_sets::CharacterSets;
+pub use charset::Charset;
+pub use crack_time::{
+    estimate_crack_times, estimate_crack_times_from_entropy, AttackScenario, CrackTime, CrackTimes,
+};
+pub use dictionary::{find_common_words, use_dictionary_file, Dictionary, DictionaryHit};
+pub use error::SecurepassError;
+pub use estimate::{
+    estimate_password_strength, estimate_password_strength_with_dictionary, MatchPattern, PasswordEstimate,
+    PasswordMatch,
+};
+pub use feedback::{
+    check_password_strength_report, check_password_strength_report_with_dictionary, FeedbackSuggestion,
+    FeedbackWarning, StrengthReport,
+};
+pub use memorable::{MemorableOptions, MemorablePassword};
+pub use minimal_balance::{
+    balance_password_minimally, balance_password_minimally_with_rng, MinimalBalance, PasswordEdit,
+};
+pub use normalization::{normalize, Normalization};
+pub use passphrase::PassphraseOptions;
+pub use pattern::{PasswordPattern, PatternError};
+pub use pin::{check_pin_strength, check_pin_weakness, PinOptions, PinWeakness};
+pub use policy::{CharacterClass, PasswordPolicy, PolicyViolation};
+pub use presets::{AsvsLevel, PolicyPreset};
+pub use pronounceable::PronounceableOptions;
+pub use site::{SiteAlgorithm, SiteOptions};
+pub use substitution::SubstitutionTable;
 
 /// Structure representing the specification of a password.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PasswordSpecification {
     /// Whether the password contains lowercase characters.
     pub has_lowercase: bool,
@@ -15,9 +66,13 @@ pub struct PasswordSpecification {
     pub has_special: bool,
     /// Whether the password contains numerical characters.
     pub has_number: bool,
+    /// Whether the password contains characters of the Unicode class, like
+    /// accented letters.
+    pub has_unicode: bool,
 }
 
-/// Enum representing the strength of a password.
+/// Enum representing the strength of a password, ordered from weakest to strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PasswordStrength {
     /// Weak password strength.
     Weak,
@@ -37,16 +92,69 @@ pub struct PasswordOptions {
     pub include_uppercase: bool,
     /// Whether to include numerical characters in the password.
     pub include_numbers: bool,
+    /// Whether to include characters of the Unicode class, accented letters
+    /// by default, in the password.
+    pub include_unicode: bool,
     /// Whether to balance the password to ensure it meets strength criteria.
+    /// A balanced password has at least one character of every class and at
+    /// least 13 characters, more if the character sets are too small for a
+    /// strong password of that length.
     pub with_balancing: bool,
-    /// Optional phrase to be included in the password.
+    /// Minimum number of lowercase letters.
+    pub min_lowercase: usize,
+    /// Minimum number of uppercase letters. Uppercase letters are included
+    /// when it is not zero.
+    pub min_uppercase: usize,
+    /// Minimum number of digits. Digits are included when it is not zero.
+    pub min_numbers: usize,
+    /// Minimum number of special characters. Special characters are included
+    /// when it is not zero.
+    pub min_special_chars: usize,
+    /// Whether to exclude characters that are easily confused when read,
+    /// like `0` and `O` or `1`, `l` and `I`.
+    pub exclude_ambiguous: bool,
+    /// Characters that must never appear in generated or balanced passwords.
+    pub excluded_chars: String,
+    /// Characters of every class, also used to measure the entropy of
+    /// generated and balanced passwords.
+    pub character_sets: CharacterSets,
+    /// Normalization applied to phrases and to passwords before they are
+    /// balanced or measured.
+    pub normalization: Normalization,
+    /// Optional phrase to be included in the password. Its characters are
+    /// drawn at random, see `memorable` to keep the phrase recognizable.
     pub phrase: Option<String>,
+    /// Optional passphrase options. When set, whole words are picked from
+    /// the bundled word list instead of single characters.
+    pub passphrase: Option<PassphraseOptions>,
+    /// Optional pronounceable password options. When set, the password is
+    /// made of consonant-vowel syllables instead of random characters.
+    pub pronounceable: Option<PronounceableOptions>,
+    /// Optional PIN options. When set, a numeric PIN is generated instead.
+    pub pin: Option<PinOptions>,
+    /// Optional memorable password options. When set, the password is
+    /// derived from a phrase by transforming its words instead.
+    pub memorable: Option<MemorableOptions>,
+    /// Optional dictionary used to check the strength of the password while
+    /// balancing it. The built-in common words are used when unset.
+    pub dictionary: Option<Dictionary>,
 }
 
-const UPPERCASE_CHARSET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-const LOWERCASE_CHARSET: &str = "abcdefghijklmnopqrstuvwxyz";
-const NUMBERS: &str = "0123456789";
-const SPECIAL_CHARSET: &str = "!@#$%^&*?(){}[]<>-_=+";
+pub(crate) const UPPERCASE_CHARSET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub(crate) const LOWERCASE_CHARSET: &str = "abcdefghijklmnopqrstuvwxyz";
+pub(crate) const NUMBERS: &str = "0123456789";
+pub(crate) const SPECIAL_CHARSET: &str = "!@#$%^&*?(){}[]<>-_=+";
+/// Accented Latin letters of the opt-in Unicode class.
+pub(crate) const UNICODE_CHARSET: &str =
+    "àáâãäåæçèéêëìíîïñòóôõöøùúûüýÿāăąćčďđēėęěğīįłńňōőœřśşšťūůűųźżžÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏÑÒÓÔÕÖØÙÚÛÜÝŸĀĂĄĆČĎĐĒĖĘĚĞĪĮŁŃŇŌŐŒŘŚŞŠŤŪŮŰŲŹŻŽ";
+pub(crate) const AMBIGUOUS_CHARSET: &str = "B8G6I1l|0OQDS5Z2(){}[]<>";
+pub(crate) const MIN_PASSWORD_LENGTH: usize = 10;
+/// Entropy from which a password without common words is strong.
+pub(crate) const MIN_STRONG_ENTROPY: f64 = 82.0;
+/// Entropy from which a password containing common words is still strong.
+pub(crate) const MIN_STRONG_ENTROPY_WITH_COMMON_WORDS: f64 = 85.0;
+/// Number of candidates tried before requirements are reported as unsatisfiable.
+pub(crate) const MAX_GENERATION_ATTEMPTS: usize = 100;
 
 impl Default for PasswordOptions {
     /// Returns the default password options.
@@ -56,8 +164,22 @@ impl Default for PasswordOptions {
             include_special_chars: true,
             include_uppercase: true,
             include_numbers: true,
+            include_unicode: false,
             with_balancing: true,
+            min_lowercase: 0,
+            min_uppercase: 0,
+            min_numbers: 0,
+            min_special_chars: 0,
+            exclude_ambiguous: false,
+            excluded_chars: String::new(),
+            character_sets: CharacterSets::default(),
+            normalization: Normalization::default(),
             phrase: None,
+            passphrase: None,
+            pronounceable: None,
+            pin: None,
+            memorable: None,
+            dictionary: None,
         }
     }
 }
@@ -67,22 +189,264 @@ impl PasswordOptions {
     ///
     /// # Returns
     ///
-    /// A `Result` which is `Ok` with the generated password if successful, 
-    /// or an `Err` with a message if the password length is less than 10.
-    pub fn generate_password(&self) -> Result<String, String> {
-        let length = self.length;
-        if length < 10 {
-            return Err(String::from("Password length less than 10 is considered weak."));
+    /// A `Result` which is `Ok` with the generated password if successful,
+    /// or an `Err` with [`SecurepassError::TooShort`] if the password length
+    /// is less than 10, [`SecurepassError::EmptyCharset`] if the phrase has
+    /// no characters, [`SecurepassError::UnsatisfiableRequirements`] if the
+    /// minimum counts do not fit in the length or no balanced password was
+    /// found, or the error of a failed dictionary lookup. When `passphrase`
+    /// is set, the result of [`PassphraseOptions::generate_passphrase`] is
+    /// returned instead, when `pronounceable` is set, the result of
+    /// [`PronounceableOptions::generate_pronounceable_password`], when `pin`
+    /// is set, the result of [`PinOptions::generate_pin`], and when
+    /// `memorable` is set, the password of
+    /// [`MemorableOptions::generate_memorable_password`].
+    pub fn generate_password(&self) -> Result<String, SecurepassError> {
+        self.generate_password_with_rng(&mut thread_rng())
+    }
+
+    /// Generates a password based on the specified options using the given
+    /// random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`PasswordOptions::generate_password`].
+    pub fn generate_password_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<String, SecurepassError> {
+        self.generator()?.generate_password_with_rng(rng)
+    }
+
+    /// Prepares the charsets and dictionary of the options, which are then
+    /// reused for every generated password.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the generator, or an `Err` with
+    /// [`SecurepassError::TooShort`] if the password length is less than 10,
+    /// or the error of a failed dictionary lookup.
+    pub(crate) fn generator(&self) -> Result<PasswordGenerator<'_>, SecurepassError> {
+        let mut generator = PasswordGenerator {
+            options: self,
+            charset: Charset::default(),
+            required: Vec::new(),
+            length: self.length,
+            dictionary: None,
+        };
+        if self.passphrase.is_some() || self.pronounceable.is_some() || self.pin.is_some() || self.memorable.is_some() {
+            return Ok(generator);
         }
-        let charset = self.generate_charset();
 
-        let mut password: String = generate_random_password(&charset, length);
+        if self.length < MIN_PASSWORD_LENGTH {
+            return Err(SecurepassError::TooShort {
+                length: self.length,
+                min_length: MIN_PASSWORD_LENGTH,
+            });
+        }
+        generator.charset = Charset::new(&self.generate_charset());
+        if self.phrase.is_some() {
+            return Ok(generator);
+        }
 
-        if self.phrase.is_none() && self.with_balancing {
-            password = balance_password(&mut password);
+        generator.required = self.required_charsets();
+        if self.with_balancing {
+            generator.length = self.length.max(self.balanced_length());
+            generator.dictionary = Some(match &self.dictionary {
+                Some(dictionary) => dictionary.clone(),
+                None => Dictionary::builtin()?,
+            });
         }
 
-        Ok(password)
+        Ok(generator)
+    }
+
+    /// Balances a password using the character sets of the options, so
+    /// excluded characters are never added.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A mutable string reference to the password to balance.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`balance_password`].
+    pub fn balance_password(&self, password: &mut String) -> Result<String, SecurepassError> {
+        self.balance_password_with_rng(password, &mut thread_rng())
+    }
+
+    /// Balances a password using the character sets of the options and the
+    /// given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A mutable string reference to the password to balance.
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`balance_password`].
+    pub fn balance_password_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        password: &mut String,
+        rng: &mut R,
+    ) -> Result<String, SecurepassError> {
+        match &self.dictionary {
+            Some(dictionary) => self.balance(password, dictionary, rng),
+            None => self.balance(password, &Dictionary::builtin()?, rng),
+        }
+    }
+
+    /// Balances a password, checking its strength against the given dictionary.
+    ///
+    /// The password is padded from the charset of the options to the length
+    /// of a balanced password, then every missing class replaces one
+    /// character that is not the last of its class, or is appended when
+    /// there is none. Characters placed this way are never replaced again.
+    /// Finally characters are appended until the password is strong, which
+    /// takes at most as many characters as reaching the entropy of a strong
+    /// password with common words, so the number of steps is bounded.
+    fn balance<R: RngCore + CryptoRng>(
+        &self,
+        password: &mut String,
+        dictionary: &Dictionary,
+        rng: &mut R,
+    ) -> Result<String, SecurepassError> {
+        let charset = Charset::new(&self.generate_charset());
+        let mut chars: Vec<char> = normalize(password, self.normalization).chars().collect();
+
+        let optimal_password_length = self.balanced_length();
+        if chars.len() < optimal_password_length {
+            let missing = optimal_password_length - chars.len();
+            charset.extend_with_rng(&mut chars, missing, rng)?;
+        }
+
+        let sets: Vec<Vec<char>> = self
+            .class_charsets()
+            .iter()
+            .filter(|set| !set.is_empty())
+            .map(|set| set.chars().collect())
+            .collect();
+        let mut placed: Vec<usize> = Vec::with_capacity(sets.len());
+        for set in &sets {
+            if chars.iter().any(|c| set.contains(c)) {
+                continue;
+            }
+
+            let count_in = |other: &Vec<char>| chars.iter().filter(|c| other.contains(c)).count();
+            let replaceable: Vec<usize> = (0..chars.len())
+                .filter(|index| !placed.contains(index))
+                .filter(|index| sets.iter().all(|other| !other.contains(&chars[*index]) || count_in(other) > 1))
+                .collect();
+            let char_to_add = *set.choose(rng).expect("empty sets are filtered out");
+            match replaceable.choose(rng) {
+                Some(&index) => {
+                    chars[index] = char_to_add;
+                    placed.push(index);
+                }
+                None => {
+                    chars.push(char_to_add);
+                    placed.push(chars.len() - 1);
+                }
+            }
+        }
+
+        let present = self.character_sets.classes().map(|set| chars.iter().any(|c| set.contains(*c)));
+        let bits_per_char = (self.character_sets.pool_size(present) as f64).log2();
+        let max_length = if bits_per_char > 0.0 && !charset.is_empty() {
+            chars.len().max((MIN_STRONG_ENTROPY_WITH_COMMON_WORDS / bits_per_char).ceil() as usize)
+        } else {
+            chars.len()
+        };
+        loop {
+            let candidate: String = chars.iter().collect();
+            if self.check_password_strength(&candidate, dictionary) == PasswordStrength::Strong {
+                *password = candidate.clone();
+                return Ok(candidate);
+            }
+            if chars.len() >= max_length {
+                break;
+            }
+            charset.extend_with_rng(&mut chars, 1, rng)?;
+        }
+
+        Err(SecurepassError::UnsatisfiableRequirements(format!(
+            "the password is not strong with {} characters from the allowed characters",
+            max_length
+        )))
+    }
+
+    /// Returns the minimum count of every character class with its charset.
+    ///
+    /// When balancing, every class with allowed characters is required at
+    /// least once.
+    fn class_minimums(&self) -> [(String, usize); 5] {
+        let [lowercase, uppercase, numbers, special, unicode] = self.class_charsets();
+        let minimum = |set: &String, count: usize| {
+            let balanced = usize::from(self.with_balancing && !set.is_empty());
+            count.max(balanced)
+        };
+
+        [
+            (minimum(&lowercase, self.min_lowercase), lowercase),
+            (minimum(&uppercase, self.min_uppercase), uppercase),
+            (minimum(&numbers, self.min_numbers), numbers),
+            (minimum(&special, self.min_special_chars), special),
+            (minimum(&unicode, 0), unicode),
+        ]
+        .map(|(count, set)| (set, count))
+    }
+
+    /// Returns the precompiled charsets of the classes with the minimum
+    /// number of characters to draw from each.
+    pub(crate) fn required_charsets(&self) -> Vec<(Charset, usize)> {
+        self.class_minimums()
+            .into_iter()
+            .map(|(set, count)| (Charset::new(&set), count))
+            .collect()
+    }
+
+    /// Returns the minimum length of a balanced password, long enough for a
+    /// password with every class, without the excluded characters, to reach
+    /// the entropy of a strong password.
+    fn balanced_length(&self) -> usize {
+        let bits_per_char = Charset::new(&self.class_charsets().concat()).bits_per_char();
+        let optimal_password_length = PasswordOptions::default().length;
+        if bits_per_char <= 0.0 {
+            return optimal_password_length;
+        }
+
+        optimal_password_length.max((MIN_STRONG_ENTROPY / bits_per_char).ceil() as usize)
+    }
+
+    /// Checks the strength of a password with the entropy of the configured
+    /// character sets.
+    fn check_password_strength(&self, password: &str, dictionary: &Dictionary) -> PasswordStrength {
+        strength_from_entropy(password, self.character_sets.calculate_entropy(password), dictionary)
+    }
+
+    /// Returns the charset of every character class without the excluded
+    /// characters, with an empty Unicode class unless it is included.
+    fn class_charsets(&self) -> [String; 5] {
+        let mut charsets = self.character_sets.classes().map(|set| self.allowed_chars(set));
+        if !self.include_unicode {
+            charsets[4].clear();
+        }
+
+        charsets
+    }
+
+    /// Removes the excluded and, if requested, the ambiguous characters from a charset.
+    pub(crate) fn allowed_chars(&self, charset: &str) -> String {
+        charset
+            .chars()
+            .filter(|c| !self.excluded_chars.contains(*c))
+            .filter(|c| !(self.exclude_ambiguous && AMBIGUOUS_CHARSET.contains(*c)))
+            .collect()
     }
 
     /// Generates the character set for password generation based on the options.
@@ -94,26 +458,85 @@ impl PasswordOptions {
         let mut charset = String::from("");
 
         if let Some(existed_phrase) = &self.phrase {
-            charset.push_str(&remove_whitespace(existed_phrase));
+            charset.push_str(&remove_whitespace(&normalize(existed_phrase, self.normalization)));
         } else {
-            charset.push_str(LOWERCASE_CHARSET);
-            if self.include_uppercase {
-                charset.push_str(UPPERCASE_CHARSET);
+            let sets = &self.character_sets;
+            charset.push_str(&sets.lowercase);
+            if self.include_uppercase || self.min_uppercase > 0 {
+                charset.push_str(&sets.uppercase);
+            }
+            if self.include_numbers || self.min_numbers > 0 {
+                charset.push_str(&sets.numbers);
             }
-            if self.include_numbers {
-                charset.push_str(NUMBERS);
+            if self.include_special_chars || self.min_special_chars > 0 {
+                charset.push_str(&sets.special);
             }
-            if self.include_special_chars {
-                charset.push_str(SPECIAL_CHARSET);
+            if self.include_unicode {
+                charset.push_str(&sets.unicode);
             }
         }
 
-        charset
+        self.allowed_chars(&charset)
+    }
+}
+
+/// Structure representing the options of a password generation with their
+/// charsets compiled and their dictionary loaded, so generating many
+/// passwords only draws characters.
+pub(crate) struct PasswordGenerator<'a> {
+    options: &'a PasswordOptions,
+    charset: Charset,
+    required: Vec<(Charset, usize)>,
+    length: usize,
+    dictionary: Option<Dictionary>,
+}
+
+impl PasswordGenerator<'_> {
+    /// Generates a password, see [`PasswordOptions::generate_password_with_rng`].
+    pub(crate) fn generate_password_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<String, SecurepassError> {
+        let options = self.options;
+        if let Some(passphrase) = &options.passphrase {
+            return passphrase.generate_passphrase_with_rng(rng);
+        }
+        if let Some(pronounceable) = &options.pronounceable {
+            return pronounceable.generate_pronounceable_password_with_rng(rng);
+        }
+        if let Some(pin) = &options.pin {
+            return pin.generate_pin_with_rng(rng);
+        }
+        if let Some(memorable) = &options.memorable {
+            return Ok(memorable.generate_memorable_password_with_rng(rng)?.password);
+        }
+
+        if options.phrase.is_some() {
+            return self.charset.generate_password_with_rng(self.length, rng);
+        }
+        let Some(dictionary) = &self.dictionary else {
+            return generate_password_with_required_chars(&self.charset, &self.required, self.length, rng);
+        };
+        for _ in 0..MAX_GENERATION_ATTEMPTS {
+            let password = generate_password_with_required_chars(&self.charset, &self.required, self.length, rng)?;
+            if options.check_password_strength(&password, dictionary) == PasswordStrength::Strong {
+                return Ok(password);
+            }
+        }
+
+        Err(SecurepassError::UnsatisfiableRequirements(format!(
+            "no strong password was found in {} attempts",
+            MAX_GENERATION_ATTEMPTS
+        )))
     }
 }
 
 /// Generates a random password from the given character set and length.
 ///
+/// The charset is compiled for this password only. When generating many
+/// passwords from the same characters, build a [`Charset`] once and use
+/// [`Charset::generate_password`] instead.
+///
 /// # Arguments
 ///
 /// * `charset` - A string slice representing the set of characters to use.
@@ -121,15 +544,76 @@ impl PasswordOptions {
 ///
 /// # Returns
 ///
-/// A string containing the generated password.
-pub fn generate_random_password(charset: &str, length: usize) -> String {
-    let mut rng_thread = thread_rng();
-    (0..length)
-        .map(|_| {
-            let index = rng_thread.gen_range(0..charset.len());
-            charset.chars().nth(index).unwrap()
-        })
-        .collect()
+/// A `Result` which is `Ok` with the generated password, or an `Err` with
+/// [`SecurepassError::EmptyCharset`] if characters were requested from an
+/// empty charset.
+pub fn generate_random_password(charset: &str, length: usize) -> Result<String, SecurepassError> {
+    generate_random_password_with_rng(charset, length, &mut thread_rng())
+}
+
+/// Generates a random password from the given character set and length
+/// using the given random number generator.
+///
+/// # Arguments
+///
+/// * `charset` - A string slice representing the set of characters to use.
+/// * `length` - The length of the password.
+/// * `rng` - A mutable reference to a cryptographically secure random number generator.
+///
+/// # Returns
+///
+/// The same `Result` as [`generate_random_password`].
+pub fn generate_random_password_with_rng<R: RngCore + CryptoRng>(
+    charset: &str,
+    length: usize,
+    rng: &mut R,
+) -> Result<String, SecurepassError> {
+    Charset::new(charset).generate_password_with_rng(length, rng)
+}
+
+/// Generates a random password with a minimum number of characters from
+/// given sets.
+///
+/// The required characters are drawn from their sets first, the rest of the
+/// password is drawn from `charset`, and the result is shuffled uniformly so
+/// the required characters can end up anywhere.
+///
+/// # Arguments
+///
+/// * `charset` - The set of characters used for the rest of the password.
+/// * `required` - Sets of characters with the minimum number of characters
+///   to draw from each.
+/// * `length` - The length of the password.
+/// * `rng` - A mutable reference to a cryptographically secure random number generator.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with the generated password, or an `Err` with
+/// [`SecurepassError::UnsatisfiableRequirements`] if the required characters
+/// do not fit in `length`, or [`SecurepassError::EmptyCharset`] if
+/// characters were requested from an empty set.
+pub(crate) fn generate_password_with_required_chars<R: RngCore + CryptoRng>(
+    charset: &Charset,
+    required: &[(Charset, usize)],
+    length: usize,
+    rng: &mut R,
+) -> Result<String, SecurepassError> {
+    let required_count: usize = required.iter().map(|(_, count)| count).sum();
+    if required_count > length {
+        return Err(SecurepassError::UnsatisfiableRequirements(format!(
+            "{} required characters do not fit in {} characters",
+            required_count, length
+        )));
+    }
+
+    let mut chars: Vec<char> = Vec::with_capacity(length);
+    for (set, count) in required {
+        set.extend_with_rng(&mut chars, *count, rng)?;
+    }
+    charset.extend_with_rng(&mut chars, length - required_count, rng)?;
+    chars.shuffle(rng);
+
+    Ok(chars.into_iter().collect())
 }
 
 /// Balances a password to ensure it meets the specified criteria.
@@ -140,40 +624,71 @@ pub fn generate_random_password(charset: &str, length: usize) -> String {
 ///
 /// # Returns
 ///
-/// A balanced password as a string.
-pub fn balance_password(password: &mut String) -> String {
-    let mut rng_thread = thread_rng();
-    let optimal_password_length = PasswordOptions::default().length;
-
-    if password.len() < optimal_password_length {
-        let charset = PasswordOptions::default().generate_charset();
-        let number_chars_to_add = optimal_password_length - password.len();
-        if number_chars_to_add > 0 {
-            let str_to_add = generate_random_password(&charset, number_chars_to_add);
-            password.push_str(&str_to_add);
-        }
-    }
-    loop {
-        let password_str = check_password_strength(password);
-        let password_specification = check_password_specification(password);
+/// A `Result` which is `Ok` with the balanced password, or an `Err` if the
+/// dictionary used to check its strength cannot be read, or
+/// [`SecurepassError::UnsatisfiableRequirements`] if the password could not
+/// be balanced.
+pub fn balance_password(password: &mut String) -> Result<String, SecurepassError> {
+    balance_password_with_rng(password, &mut thread_rng())
+}
 
-        if let PasswordStrength::Strong = password_str {
-            if password_specification.has_lowercase
-                && password_specification.has_number
-                && password_specification.has_special
-                && password_specification.has_uppercase
-            {
-                break;
-            }
-        }
+/// Balances a password to ensure it meets the specified criteria using the
+/// given random number generator.
+///
+/// # Arguments
+///
+/// * `password` - A mutable string reference to the password to balance.
+/// * `rng` - A mutable reference to a cryptographically secure random number generator.
+///
+/// # Returns
+///
+/// The same `Result` as [`balance_password`].
+pub fn balance_password_with_rng<R: RngCore + CryptoRng>(
+    password: &mut String,
+    rng: &mut R,
+) -> Result<String, SecurepassError> {
+    balance_password_with_dictionary_and_rng(password, &Dictionary::builtin()?, rng)
+}
 
-        replace_char(password, LOWERCASE_CHARSET, &mut rng_thread);
-        replace_char(password, UPPERCASE_CHARSET, &mut rng_thread);
-        replace_char(password, NUMBERS, &mut rng_thread);
-        replace_char(password, SPECIAL_CHARSET, &mut rng_thread);
-    }
+/// Balances a password to ensure it meets the specified criteria, checking
+/// its strength against the given dictionary.
+///
+/// # Arguments
+///
+/// * `password` - A mutable string reference to the password to balance.
+/// * `dictionary` - The dictionary of guessable words.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with the balanced password, or an `Err` with
+/// [`SecurepassError::UnsatisfiableRequirements`] if the password could not
+/// be balanced.
+pub fn balance_password_with_dictionary(
+    password: &mut String,
+    dictionary: &Dictionary,
+) -> Result<String, SecurepassError> {
+    balance_password_with_dictionary_and_rng(password, dictionary, &mut thread_rng())
+}
 
-    password.to_string()
+/// Balances a password to ensure it meets the specified criteria, checking
+/// its strength against the given dictionary and using the given random
+/// number generator.
+///
+/// # Arguments
+///
+/// * `password` - A mutable string reference to the password to balance.
+/// * `dictionary` - The dictionary of guessable words.
+/// * `rng` - A mutable reference to a cryptographically secure random number generator.
+///
+/// # Returns
+///
+/// The same `Result` as [`balance_password_with_dictionary`].
+pub fn balance_password_with_dictionary_and_rng<R: RngCore + CryptoRng>(
+    password: &mut String,
+    dictionary: &Dictionary,
+    rng: &mut R,
+) -> Result<String, SecurepassError> {
+    PasswordOptions::default().balance(password, dictionary, rng)
 }
 
 /// Checks the strength of a password.
@@ -184,9 +699,42 @@ pub fn balance_password(password: &mut String) -> String {
 ///
 /// # Returns
 ///
+/// A `Result` which is `Ok` with the password strength as a `PasswordStrength`
+/// enum, or an `Err` if the common words dictionary cannot be read.
+pub fn check_password_strength(password: &str) -> Result<PasswordStrength, SecurepassError> {
+    Ok(check_password_strength_with_dictionary(password, &Dictionary::builtin()?))
+}
+
+/// Checks the strength of a password, looking up common words in the given
+/// dictionary.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password.
+/// * `dictionary` - The dictionary of guessable words, e.g. the built-in
+///   common words with a layer of company names.
+///
+/// # Returns
+///
+/// The password strength as a `PasswordStrength` enum.
+pub fn check_password_strength_with_dictionary(password: &str, dictionary: &Dictionary) -> PasswordStrength {
+    let password = normalize(password, Normalization::Nfc);
+
+    strength_from_entropy(&password, calculate_entropy(&password), dictionary)
+}
+
+/// Scores a password from its entropy and the common words it contains.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password.
+/// * `entropy` - The entropy of the password.
+/// * `dictionary` - The dictionary of guessable words.
+///
+/// # Returns
+///
 /// The password strength as a `PasswordStrength` enum.
-pub fn check_password_strength(password: &str) -> PasswordStrength {
-    let entropy = calculate_entropy(password);
+pub(crate) fn strength_from_entropy(password: &str, entropy: f64, dictionary: &Dictionary) -> PasswordStrength {
     let mut score = 0;
 
     if entropy < 40.0 {
@@ -200,9 +748,9 @@ pub fn check_password_strength(password: &str) -> PasswordStrength {
         _ => score += 3,
     }
 
-    let has_common_words = check_has_common_words(password);
+    let has_common_words = dictionary.occurs_in(password);
 
-    if has_common_words && entropy < 85.0 {
+    if has_common_words && entropy < MIN_STRONG_ENTROPY_WITH_COMMON_WORDS {
         score -= 1;
     }
 
@@ -213,7 +761,12 @@ pub fn check_password_strength(password: &str) -> PasswordStrength {
     }
 }
 
-/// Calculates the entropy of a password.
+/// Calculates the entropy of a password, counting the characters of the
+/// password normalized to NFC.
+///
+/// Every default character set the password uses adds its size to the pool,
+/// and characters outside every set, like Cyrillic, CJK, emoji or titlecase
+/// letters, add the number of distinct ones in the password.
 ///
 /// # Arguments
 ///
@@ -221,40 +774,9 @@ pub fn check_password_strength(password: &str) -> PasswordStrength {
 ///
 /// # Returns
 ///
-/// The entropy of the password as a `f64`.
+/// The entropy of the password as a `f64`, `0.0` for an empty password.
 pub fn calculate_entropy(password: &str) -> f64 {
-    let l = password.len() as f64;
-    let mut r: f64 = 0.0;
-
-    let password_specification = check_password_specification(&password);
-
-    if password_specification.has_lowercase {
-        r += LOWERCASE_CHARSET.len() as f64;
-    }
-    if password_specification.has_uppercase {
-        r += UPPERCASE_CHARSET.len() as f64;
-    }
-    if password_specification.has_number {
-        r += NUMBERS.len() as f64;
-    }
-    if password_specification.has_special {
-        r += SPECIAL_CHARSET.len() as f64;
-    }
-
-    l * r.log2()
-}
-
-/// Replaces a character in a password with a random character from a given set.
-///
-/// # Arguments
-///
-/// * `password` - A mutable string reference to the password.
-/// * `set` - A string slice representing the set of characters to use.
-/// * `rng_thread` - A mutable reference to a thread random number generator.
-fn replace_char(password: &mut String, set: &str, rng_thread: &mut rand::prelude::ThreadRng) {
-    let index = rng_thread.gen_range(0..password.len());
-    let char_to_add = set.chars().nth(rng_thread.gen_range(0..set.len())).unwrap();
-    password.replace_range(index..=index, &char_to_add.to_string());
+    CharacterSets::default().calculate_entropy(password)
 }
 
 /// Removes whitespace from a string.
@@ -270,7 +792,8 @@ fn remove_whitespace(input: &str) -> String {
     input.split_whitespace().collect()
 }
 
-/// Checks if a password contains common words.
+/// Checks if a password contains common words, ignoring case, reversal and
+/// common l33t substitutions.
 ///
 /// # Arguments
 ///
@@ -278,22 +801,27 @@ fn remove_whitespace(input: &str) -> String {
 ///
 /// # Returns
 ///
-/// `true` if the password contains common words, `false` otherwise.
-fn check_has_common_words(password: &str) -> bool {
-    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("dictionary.txt");
-    let contents = fs::read_to_string(path).expect("Cannot read file.");
-    let words: Vec<&str> = contents.lines().collect();
-
-    for word in words {
-        if password.contains(&word) {
-            return true;
-        }
-    }
+/// A `Result` which is `Ok` with `true` if the password contains common words,
+/// `false` otherwise, or an `Err` if the dictionary cannot be read.
+pub fn check_has_common_words(password: &str) -> Result<bool, SecurepassError> {
+    Ok(Dictionary::builtin()?.occurs_in(password))
+}
 
-    false
+/// Checks if a whole password is a common password, ignoring case.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with `true` if the password is in the dictionary,
+/// `false` otherwise, or an `Err` if the dictionary cannot be read.
+pub fn check_is_common_password(password: &str) -> Result<bool, SecurepassError> {
+    Ok(Dictionary::builtin()?.contains(password))
 }
 
-/// Checks the specification of a password.
+/// Checks the specification of a password, after normalizing it to NFC.
 ///
 /// # Arguments
 ///
@@ -302,23 +830,29 @@ fn check_has_common_words(password: &str) -> bool {
 /// # Returns
 ///
 /// A `PasswordSpecification` structure containing the specifications of the password.
-fn check_password_specification(password: &str) -> PasswordSpecification {
+pub fn check_password_specification(password: &str) -> PasswordSpecification {
+    let password = normalize(password, Normalization::Nfc);
     let has_lowercase = password.chars().any(|c| c.is_lowercase());
     let has_uppercase = password.chars().any(|c| c.is_uppercase());
-    let has_number = password.chars().any(|c| c.is_digit(10));
+    let has_number = password.chars().any(|c| c.is_ascii_digit());
     let has_special = password.chars().any(|c| SPECIAL_CHARSET.contains(c));
+    let has_unicode = password.chars().any(|c| UNICODE_CHARSET.contains(c));
 
     PasswordSpecification {
         has_lowercase,
         has_uppercase,
         has_number,
         has_special,
+        has_unicode,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::OsRng;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
 
     #[test]
     fn test_generate_password_with_default() {
@@ -329,7 +863,7 @@ mod tests {
         let password = result.unwrap();
         assert_eq!(password.len(), PasswordOptions::default().length);
 
-        assert!(matches!(check_password_strength(&password), PasswordStrength::Strong));
+        assert!(matches!(check_password_strength(&password), Ok(PasswordStrength::Strong)));
     }
 
     #[test]
@@ -341,7 +875,9 @@ mod tests {
 
         let result = options.generate_password();
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Password length less than 10 is considered weak.");
+        let error = result.unwrap_err();
+        assert!(matches!(error, SecurepassError::TooShort { length: 5, min_length: 10 }));
+        assert_eq!(error.to_string(), "Password length less than 10 is considered weak.");
     }
 
     #[test]
@@ -354,7 +890,7 @@ mod tests {
         let result = options.generate_password();
         let password = result.unwrap();
         assert_eq!(password.len(), 15);
-        assert!(matches!(check_password_strength(&password), PasswordStrength::Strong));
+        assert!(matches!(check_password_strength(&password), Ok(PasswordStrength::Strong)));
         assert!(password.chars().any(|c| LOWERCASE_CHARSET.contains(c)));
         assert!(password.chars().any(|c| UPPERCASE_CHARSET.contains(c)));
         assert!(password.chars().any(|c| NUMBERS.contains(c)));
@@ -375,16 +911,317 @@ mod tests {
     #[test]
     fn test_balance_password() {
         let mut password = "qwertyuiop".to_string();
-        assert!(matches!(check_password_strength(&password), PasswordStrength::Weak));
+        assert!(matches!(check_password_strength(&password), Ok(PasswordStrength::Weak)));
+
+        let balanced = balance_password(&mut password).unwrap();
+        assert!(matches!(check_password_strength(&balanced), Ok(PasswordStrength::Strong)));
+    }
+
+    #[test]
+    fn test_balance_multibyte_password() {
+        let mut password = "pässwörd€çø".to_string();
+
+        let balanced = balance_password_with_rng(&mut password, &mut ChaCha20Rng::seed_from_u64(5)).unwrap();
+        assert_eq!(balanced, password);
+        let specification = check_password_specification(&balanced);
+        assert!(specification.has_lowercase && specification.has_uppercase);
+        assert!(specification.has_number && specification.has_special);
+        assert!(matches!(check_password_strength(&balanced), Ok(PasswordStrength::Strong)));
+    }
+
+    #[test]
+    fn test_balance_password_is_deterministic() {
+        let balance = |seed| {
+            balance_password_with_rng(&mut "qwertyuiop".to_string(), &mut ChaCha20Rng::seed_from_u64(seed))
+        };
 
-        let balanced = balance_password(&mut password);
-        assert!(matches!(check_password_strength(&balanced), PasswordStrength::Strong));
+        let first = balance(9).unwrap();
+        assert_eq!(first, balance(9).unwrap());
+        let max_length = (MIN_STRONG_ENTROPY_WITH_COMMON_WORDS / 83f64.log2()).ceil() as usize;
+        assert!((PasswordOptions::default().length..=max_length).contains(&first.chars().count()));
+    }
+
+    #[test]
+    fn test_balance_password_keeps_strong_password() {
+        let mut password = "!QEa4Kta2}wg1".to_string();
+        assert_eq!(balance_password(&mut password).unwrap(), "!QEa4Kta2}wg1");
+    }
+
+    #[test]
+    fn test_balance_password_unreachable() {
+        let options = PasswordOptions {
+            character_sets: CharacterSets {
+                lowercase: String::from("a"),
+                uppercase: String::new(),
+                numbers: String::new(),
+                special: String::new(),
+                unicode: String::new(),
+            },
+            ..Default::default()
+        };
+
+        let result = options.balance_password(&mut "aaaa".to_string());
+        assert!(matches!(result, Err(SecurepassError::UnsatisfiableRequirements(_))));
+    }
+
+    #[test]
+    fn test_generate_password_with_seeded_rng() {
+        let options = PasswordOptions::default();
+
+        let first = options.generate_password_with_rng(&mut ChaCha20Rng::seed_from_u64(7)).unwrap();
+        let second = options.generate_password_with_rng(&mut ChaCha20Rng::seed_from_u64(7)).unwrap();
+        let other = options.generate_password_with_rng(&mut ChaCha20Rng::seed_from_u64(8)).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert!(matches!(check_password_strength(&first), Ok(PasswordStrength::Strong)));
+    }
+
+    #[test]
+    fn test_generate_random_password_with_os_rng() {
+        let password = generate_random_password_with_rng(NUMBERS, 8, &mut OsRng).unwrap();
+        assert_eq!(password.len(), 8);
+        assert!(password.chars().all(|c| NUMBERS.contains(c)));
     }
 
     #[test]
     fn test_check_password_strength() {
-        assert!(matches!(check_password_strength("!QEa4Kta2}wg1"), PasswordStrength::Strong));
-        assert!(matches!(check_password_strength("Medium333!@"), PasswordStrength::Medium));
-        assert!(matches!(check_password_strength("weakpassword"), PasswordStrength::Weak));
+        assert!(matches!(check_password_strength("!QEa4Kta2}wg1"), Ok(PasswordStrength::Strong)));
+        assert!(matches!(check_password_strength("Medium333!@"), Ok(PasswordStrength::Medium)));
+        assert!(matches!(check_password_strength("weakpassword"), Ok(PasswordStrength::Weak)));
+    }
+
+    #[test]
+    fn test_check_password_strength_with_dictionary() {
+        let dictionary = Dictionary::builtin().unwrap().with_layer(Dictionary::from_words(["Qvzr"]));
+        assert!(matches!(check_password_strength("Qvzr7!Xw3#L"), Ok(PasswordStrength::Medium)));
+        assert_eq!(check_password_strength_with_dictionary("Qvzr7!Xw3#L", &dictionary), PasswordStrength::Weak);
+    }
+
+    #[test]
+    fn test_generate_password_with_dictionary() {
+        let options = PasswordOptions {
+            dictionary: Some(Dictionary::builtin().unwrap().with_user_inputs(["jane.doe@acme.com"])),
+            ..Default::default()
+        };
+
+        let password = options.generate_password_with_rng(&mut ChaCha20Rng::seed_from_u64(7)).unwrap();
+        let dictionary = options.dictionary.as_ref().unwrap();
+        assert_eq!(check_password_strength_with_dictionary(&password, dictionary), PasswordStrength::Strong);
+    }
+
+    #[test]
+    fn test_generate_password_with_minimums() {
+        let options = PasswordOptions {
+            length: 12,
+            include_uppercase: false,
+            with_balancing: false,
+            min_uppercase: 1,
+            min_numbers: 2,
+            min_special_chars: 3,
+            ..Default::default()
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(13);
+        for _ in 0..50 {
+            let password = options.generate_password_with_rng(&mut rng).unwrap();
+            assert_eq!(password.len(), 12);
+            assert!(password.chars().filter(|c| UPPERCASE_CHARSET.contains(*c)).count() >= 1);
+            assert!(password.chars().filter(|c| NUMBERS.contains(*c)).count() >= 2);
+            assert!(password.chars().filter(|c| SPECIAL_CHARSET.contains(*c)).count() >= 3);
+        }
+    }
+
+    #[test]
+    fn test_generate_password_with_too_many_minimums() {
+        let options = PasswordOptions {
+            length: 10,
+            min_numbers: 6,
+            min_special_chars: 6,
+            with_balancing: false,
+            ..Default::default()
+        };
+
+        let result = options.generate_password();
+        assert!(matches!(result, Err(SecurepassError::UnsatisfiableRequirements(_))));
+    }
+
+    #[test]
+    fn test_generate_balanced_password_has_every_class() {
+        let options = PasswordOptions {
+            length: 10,
+            include_special_chars: false,
+            ..Default::default()
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(21);
+        for _ in 0..20 {
+            let password = options.generate_password_with_rng(&mut rng).unwrap();
+            let specification = check_password_specification(&password);
+            assert_eq!(password.len(), 13);
+            assert!(specification.has_lowercase && specification.has_uppercase);
+            assert!(specification.has_number && specification.has_special);
+            assert!(matches!(check_password_strength(&password), Ok(PasswordStrength::Strong)));
+        }
+    }
+
+    #[test]
+    fn test_generate_password_without_excluded_chars() {
+        let options = PasswordOptions {
+            exclude_ambiguous: true,
+            excluded_chars: String::from("aeiou!"),
+            min_special_chars: 2,
+            ..Default::default()
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(5);
+        for _ in 0..50 {
+            let password = options.generate_password_with_rng(&mut rng).unwrap();
+            assert!(!password.chars().any(|c| AMBIGUOUS_CHARSET.contains(c) || "aeiou!".contains(c)));
+        }
+    }
+
+    #[test]
+    fn test_balance_password_without_excluded_chars() {
+        let options = PasswordOptions {
+            exclude_ambiguous: true,
+            excluded_chars: String::from("#$%"),
+            ..Default::default()
+        };
+
+        let mut password = "qwertyuiop".to_string();
+        let balanced = options.balance_password_with_rng(&mut password, &mut ChaCha20Rng::seed_from_u64(3)).unwrap();
+        let added = balanced.chars().filter(|c| !"qwertyuiop".contains(*c));
+        assert!(added.clone().all(|c| !AMBIGUOUS_CHARSET.contains(c) && !"#$%".contains(c)));
+        assert!(matches!(check_password_strength(&balanced), Ok(PasswordStrength::Strong)));
+    }
+
+    #[test]
+    fn test_balanced_length_without_excluded_chars() {
+        let options = PasswordOptions {
+            exclude_ambiguous: true,
+            ..Default::default()
+        };
+
+        assert_eq!(PasswordOptions::default().balanced_length(), 13);
+        assert_eq!(options.balanced_length(), 14);
+        let password = options
+            .generate_password_with_rng(&mut ChaCha20Rng::seed_from_u64(5))
+            .unwrap();
+        assert!(password.chars().count() >= 14);
+    }
+
+    #[test]
+    fn test_generate_password_with_excluded_class() {
+        let options = PasswordOptions {
+            excluded_chars: String::from(NUMBERS),
+            with_balancing: false,
+            min_numbers: 1,
+            ..Default::default()
+        };
+
+        assert!(matches!(options.generate_password(), Err(SecurepassError::EmptyCharset)));
+    }
+
+    #[test]
+    fn test_generate_password_with_custom_sets() {
+        let options = PasswordOptions {
+            character_sets: CharacterSets {
+                special: String::from(".,;"),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(9);
+        for _ in 0..20 {
+            let password = options.generate_password_with_rng(&mut rng).unwrap();
+            let specification = options.character_sets.check_password_specification(&password);
+            assert_eq!(password.len(), 14);
+            assert!(specification.has_special);
+            assert!(!password.chars().any(|c| SPECIAL_CHARSET.contains(c)));
+            assert!(options.character_sets.calculate_entropy(&password) >= MIN_STRONG_ENTROPY);
+        }
+    }
+
+    #[test]
+    fn test_generate_password_from_unicode_phrase() {
+        let options = PasswordOptions {
+            phrase: Some("zażółć gęślą".to_string()),
+            ..Default::default()
+        };
+
+        for seed in 0..20 {
+            let password = options.generate_password_with_rng(&mut ChaCha20Rng::seed_from_u64(seed)).unwrap();
+            assert_eq!(password.chars().count(), 13);
+            assert!(password.chars().all(|c| "zażółćgęślą".contains(c)));
+        }
+    }
+
+    #[test]
+    fn test_calculate_entropy_counts_chars() {
+        let pool = (26 + UNICODE_CHARSET.chars().count()) as f64;
+        assert!((calculate_entropy("zażółćgęślą") - 11.0 * pool.log2()).abs() < 1e-9);
+        assert_eq!(calculate_entropy("zaz\u{307}o\u{301}\u{142}c\u{301}"), calculate_entropy("zażółć"));
+        assert!(check_password_specification("e\u{301}").has_unicode);
+        assert!(!check_password_specification("Password1!").has_unicode);
+    }
+
+    #[test]
+    fn test_calculate_entropy_outside_every_class() {
+        assert!((calculate_entropy("日本語パスワード") - 8.0 * 8f64.log2()).abs() < 1e-9);
+        assert_eq!(calculate_entropy("😀😀😀😀"), 0.0);
+        assert!((calculate_entropy("😀🎉😀🎉") - 4.0).abs() < 1e-9);
+        assert_eq!(calculate_entropy("ǅǅǅ"), 0.0);
+        assert!((calculate_entropy("ǅa") - 2.0 * 27f64.log2()).abs() < 1e-9);
+        assert!((calculate_entropy("пароль") - 6.0 * 6f64.log2()).abs() < 1e-9);
+        assert_eq!(calculate_entropy(""), 0.0);
+        for password in ["日本語パスワード", "😀😀😀😀", "ǅǅǅ", "пароль", ""] {
+            assert!(calculate_entropy(password).is_finite());
+            assert!(check_password_strength(password).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_generate_password_with_unicode() {
+        let options = PasswordOptions {
+            include_unicode: true,
+            ..Default::default()
+        };
+
+        let password = options.generate_password_with_rng(&mut ChaCha20Rng::seed_from_u64(12)).unwrap();
+        assert!(password.chars().any(|c| UNICODE_CHARSET.contains(c)));
+        assert!(password.chars().count() >= options.length);
+        assert!(PasswordOptions::default().generate_password().unwrap().is_ascii());
+    }
+
+    #[test]
+    fn test_balance_password_normalizes() {
+        let options = PasswordOptions {
+            normalization: Normalization::Nfkc,
+            ..Default::default()
+        };
+
+        for seed in 0..20 {
+            let mut password = "\u{fb01}e\u{301}".to_string();
+            let balanced = options.balance_password_with_rng(&mut password, &mut ChaCha20Rng::seed_from_u64(seed)).unwrap();
+
+            assert_eq!(balanced, normalize(&balanced, Normalization::Nfkc));
+            assert!(!balanced.contains('\u{fb01}') && !balanced.contains('\u{301}'));
+            assert!(balanced.chars().count() >= options.balanced_length());
+            let specification = check_password_specification(&balanced);
+            assert!(specification.has_lowercase && specification.has_uppercase);
+            assert!(specification.has_number && specification.has_special);
+        }
+    }
+
+    #[test]
+    fn test_generate_password_from_empty_phrase() {
+        let options = PasswordOptions {
+            phrase: Some("   ".to_string()),
+            ..Default::default()
+        };
+
+        let result = options.generate_password();
+        assert!(matches!(result, Err(SecurepassError::EmptyCharset)));
     }
 }
@@ -0,0 +1,291 @@
+//! Memorable passwords derived from a phrase chosen by the user.
+
+use crate::{
+    estimate_password_strength, normalize, CharacterClass, Normalization, PasswordPolicy, PasswordStrength, SecurepassError, SubstitutionTable,
+    NUMBERS, SPECIAL_CHARSET,
+};
+use rand::seq::SliceRandom;
+use rand::{thread_rng, CryptoRng, Rng, RngCore};
+
+/// Substitutions applied by default, which keep the words easy to read back.
+const MEMORABLE_SUBSTITUTIONS: [(char, &str); 4] = [('0', "o"), ('3', "e"), ('4', "a"), ('$', "s")];
+
+/// Structure representing the options for deriving a memorable password
+/// from a phrase.
+///
+/// With the default options, "correct horse battery staple" becomes
+/// "C0rrectH0rseB4tterySt4ple" with a random digit and special character
+/// inserted between the words, and "Chb$" with `first_letters` before
+/// balancing adds characters to it.
+pub struct MemorableOptions {
+    /// The phrase the password is derived from.
+    pub phrase: String,
+    /// Whether to keep only the first character of every word of the phrase.
+    pub first_letters: bool,
+    /// Whether to capitalize the first letter of every word, which are
+    /// otherwise lowercase. With `first_letters`, only the first initial is
+    /// capitalized and the case of the other initials is kept from the phrase.
+    pub capitalize: bool,
+    /// Optional table of mnemonic substitutions. When set, the first
+    /// letters of every word with a substitution are replaced with their
+    /// first symbol, like "a" with "4" or "s" with "$". The default table
+    /// only replaces "o", "e", "a" and "s".
+    pub substitutions: Option<SubstitutionTable>,
+    /// Maximum number of letters substituted in every word.
+    pub substitutions_per_word: usize,
+    /// Separator placed between the words.
+    pub separator: String,
+    /// Number of random digits inserted between the words.
+    pub digits: usize,
+    /// Number of random special characters inserted between the words.
+    pub special_chars: usize,
+    /// Strength the password is balanced to.
+    pub strength: PasswordStrength,
+}
+
+impl Default for MemorableOptions {
+    /// Returns the default memorable password options, with an empty phrase.
+    fn default() -> Self {
+        Self {
+            phrase: String::new(),
+            first_letters: false,
+            capitalize: true,
+            substitutions: Some(
+                MEMORABLE_SUBSTITUTIONS
+                    .iter()
+                    .fold(SubstitutionTable::new(), |table, (symbol, letters)| {
+                        table.with_substitution(*symbol, letters)
+                    }),
+            ),
+            substitutions_per_word: 1,
+            separator: String::new(),
+            digits: 1,
+            special_chars: 1,
+            strength: PasswordStrength::Strong,
+        }
+    }
+}
+
+/// Structure representing a memorable password with its entropy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemorablePassword {
+    /// The generated password.
+    pub password: String,
+    /// Entropy of the password in bits: the estimated guesses of the
+    /// transformed phrase, as if the phrase were unknown to the attacker,
+    /// plus the bits of every random character added to it.
+    pub entropy: f64,
+}
+
+impl MemorableOptions {
+    /// Derives a memorable password from the phrase.
+    ///
+    /// The words of the phrase are transformed, random digits and special
+    /// characters are inserted between them, and the result is balanced with
+    /// [`PasswordPolicy::balance_password_minimally`] until it has at least
+    /// 10 characters and the requested strength.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the password and its entropy if
+    /// successful, or an `Err` with [`SecurepassError::EmptyCharset`] if the
+    /// phrase has no word, the error of a failed balancing, or of a failed
+    /// dictionary lookup.
+    pub fn generate_memorable_password(&self) -> Result<MemorablePassword, SecurepassError> {
+        self.generate_memorable_password_with_rng(&mut thread_rng())
+    }
+
+    /// Derives a memorable password from the phrase using the given random
+    /// number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`MemorableOptions::generate_memorable_password`].
+    pub fn generate_memorable_password_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<MemorablePassword, SecurepassError> {
+        let words = self.transformed_words();
+        if words.is_empty() {
+            return Err(SecurepassError::EmptyCharset);
+        }
+        let estimate = estimate_password_strength(&words.join(&self.separator))?;
+        let mut entropy = estimate.guesses_log10 * 10f64.log2();
+
+        let mut gaps = vec![String::new(); words.len() + 1];
+        let gap_bits = (gaps.len() as f64).log2();
+        for (charset, count) in [(NUMBERS, self.digits), (SPECIAL_CHARSET, self.special_chars)] {
+            let chars: Vec<char> = charset.chars().collect();
+            for _ in 0..count {
+                let gap = rng.gen_range(0..gaps.len());
+                gaps[gap].push(*chars.choose(rng).ok_or(SecurepassError::EmptyCharset)?);
+                entropy += (chars.len() as f64).log2() + gap_bits;
+            }
+        }
+
+        let mut password = gaps[0].clone();
+        for (index, word) in words.iter().enumerate() {
+            if index > 0 {
+                password.push_str(&self.separator);
+            }
+            password.push_str(word);
+            password.push_str(&gaps[index + 1]);
+        }
+
+        let policy = PasswordPolicy {
+            min_strength: Some(self.strength),
+            ..Default::default()
+        };
+        let balanced = policy.balance_password_minimally_with_rng(&password, rng)?;
+        for edit in &balanced.edits {
+            entropy += added_char_bits(edit.character());
+        }
+
+        Ok(MemorablePassword {
+            password: balanced.password,
+            entropy,
+        })
+    }
+
+    /// Returns the words of the phrase with the transformations applied.
+    fn transformed_words(&self) -> Vec<String> {
+        normalize(&self.phrase, Normalization::Nfc)
+            .split_whitespace()
+            .enumerate()
+            .map(|(index, word)| {
+                let word: String = if self.first_letters {
+                    word.chars().take(1).collect()
+                } else {
+                    word.to_lowercase()
+                };
+                let capitalize = self.capitalize && (!self.first_letters || index == 0);
+                let mut substituted = 0;
+
+                word.chars()
+                    .enumerate()
+                    .map(|(position, c)| {
+                        if capitalize && position == 0 {
+                            return c.to_uppercase().next().unwrap_or(c);
+                        }
+                        let symbol = match &self.substitutions {
+                            Some(table) if substituted < self.substitutions_per_word => {
+                                table.symbols(c).first().copied()
+                            }
+                            _ => None,
+                        };
+                        match symbol {
+                            Some(symbol) => {
+                                substituted += 1;
+                                symbol
+                            }
+                            None => c,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Returns the bits of a character added while balancing, picked uniformly
+/// from the characters of its class.
+fn added_char_bits(c: char) -> f64 {
+    [
+        CharacterClass::Number,
+        CharacterClass::Special,
+        CharacterClass::Uppercase,
+        CharacterClass::Lowercase,
+    ]
+    .into_iter()
+    .find(|class| class.contains(c))
+    .map_or(0.0, |class| (class.charset().chars().count() as f64).log2())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{check_password_strength, PasswordOptions};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_transformed_words() {
+        let options = MemorableOptions {
+            phrase: String::from("correct  horse battery staple"),
+            ..Default::default()
+        };
+        assert_eq!(options.transformed_words(), vec!["C0rrect", "H0rse", "B4ttery", "St4ple"]);
+
+        let options = MemorableOptions {
+            phrase: String::from("correct horse battery staple"),
+            capitalize: false,
+            substitutions: Some(SubstitutionTable::default()),
+            substitutions_per_word: 2,
+            ..Default::default()
+        };
+        assert_eq!(options.transformed_words(), vec!["(0rrect", "h0r$e", "84ttery", "$+aple"]);
+
+        let options = MemorableOptions {
+            phrase: String::from("my Dog eats 2 socks"),
+            first_letters: true,
+            substitutions: None,
+            ..Default::default()
+        };
+        assert_eq!(options.transformed_words().concat(), "MDe2s");
+    }
+
+    #[test]
+    fn test_generate_memorable_password() {
+        let options = MemorableOptions {
+            phrase: String::from("correct horse battery staple"),
+            ..Default::default()
+        };
+
+        let memorable = options
+            .generate_memorable_password_with_rng(&mut ChaCha20Rng::seed_from_u64(6))
+            .unwrap();
+        assert_eq!(check_password_strength(&memorable.password).unwrap(), PasswordStrength::Strong);
+        for word in options.transformed_words() {
+            assert!(memorable.password.contains(&word));
+        }
+        assert!(memorable.entropy > (10f64 * 21.0 * 25.0).log2());
+    }
+
+    #[test]
+    fn test_memorable_entropy_is_lower_than_character_entropy() {
+        let options = MemorableOptions {
+            phrase: String::from("I love my cat Felix since 2015"),
+            first_letters: true,
+            strength: PasswordStrength::Medium,
+            ..Default::default()
+        };
+
+        let memorable = options.generate_memorable_password().unwrap();
+        assert!(memorable.password.chars().count() >= 10);
+        assert!(memorable.entropy < crate::calculate_entropy(&memorable.password));
+    }
+
+    #[test]
+    fn test_generate_memorable_password_from_empty_phrase() {
+        let result = MemorableOptions::default().generate_memorable_password();
+        assert!(matches!(result, Err(SecurepassError::EmptyCharset)));
+    }
+
+    #[test]
+    fn test_generate_password_with_memorable() {
+        let options = PasswordOptions {
+            memorable: Some(MemorableOptions {
+                phrase: String::from("correct horse battery staple"),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let password = options.generate_password().unwrap();
+        assert_eq!(check_password_strength(&password).unwrap(), PasswordStrength::Strong);
+    }
+}
@@ -0,0 +1,653 @@
+//! Balancing that keeps the user's password and reports every change made.
+
+use crate::{
+    normalize, CharacterClass, CharacterSets, Dictionary, Normalization, PasswordPolicy, PasswordStrength,
+    PolicyViolation, SecurepassError, MAX_GENERATION_ATTEMPTS, MIN_STRONG_ENTROPY,
+};
+use rand::seq::SliceRandom;
+use rand::{thread_rng, CryptoRng, Rng, RngCore};
+
+/// Classes added to a password that is too short or too weak, in order of
+/// preference when none of them is required by the policy.
+const ADDED_CLASSES: [CharacterClass; 4] = [
+    CharacterClass::Number,
+    CharacterClass::Special,
+    CharacterClass::Uppercase,
+    CharacterClass::Lowercase,
+];
+
+/// Minimum entropy of a medium password without common words.
+const MIN_MEDIUM_ENTROPY: f64 = 60.0;
+
+/// Number of placements of the added characters tried for every edit count.
+const PLACEMENT_ATTEMPTS: usize = 32;
+
+/// Enum representing one change made to a password while balancing it.
+///
+/// Positions are indices of characters in the balanced password, which is
+/// normalized to NFC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordEdit {
+    /// A character was inserted.
+    Inserted {
+        /// Index of the inserted character.
+        position: usize,
+        /// The inserted character.
+        character: char,
+    },
+    /// A character of the password was replaced.
+    Substituted {
+        /// Index of the replaced character.
+        position: usize,
+        /// The character of the original password.
+        previous: char,
+        /// The character that replaced it.
+        character: char,
+    },
+}
+
+impl PasswordEdit {
+    /// Returns the index of the edited character in the balanced password.
+    pub fn position(&self) -> usize {
+        match self {
+            Self::Inserted { position, .. } | Self::Substituted { position, .. } => *position,
+        }
+    }
+
+    /// Returns the character added by the edit.
+    pub fn character(&self) -> char {
+        match self {
+            Self::Inserted { character, .. } | Self::Substituted { character, .. } => *character,
+        }
+    }
+
+    /// Returns a mutable reference to the position of the edit.
+    fn position_mut(&mut self) -> &mut usize {
+        match self {
+            Self::Inserted { position, .. } | Self::Substituted { position, .. } => position,
+        }
+    }
+}
+
+/// Structure representing a balanced password with the edits made to the
+/// original one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinimalBalance {
+    /// The balanced password.
+    pub password: String,
+    /// The edits made, in the order they were made.
+    pub edits: Vec<PasswordEdit>,
+}
+
+/// Structure representing a password being balanced against a policy.
+#[derive(Clone)]
+struct Balancer<'a> {
+    policy: &'a PasswordPolicy,
+    chars: Vec<char>,
+    edits: Vec<PasswordEdit>,
+}
+
+impl PasswordPolicy {
+    /// Balances a password with a few small edits so that it follows the
+    /// policy.
+    ///
+    /// Every broken rule other than the length, entropy and strength targets
+    /// is fixed in turn by inserting characters, at the end of the password
+    /// or inside the run or common word that breaks the rule, so the original
+    /// password stays readable. An added character is of a class the policy
+    /// still requires, so when the policy only sets a length and minimums of
+    /// lowercase, uppercase, digits and special characters, the number of
+    /// edits is the larger of the length deficit and the sum of the class
+    /// deficits, which no balancing can go below.
+    ///
+    /// The length, entropy and strength targets are then reached together
+    /// with the fewest edits: the edit count is searched upward from the
+    /// smallest one whose added length and missing classes can reach the
+    /// target entropy, and every count is tried with the added characters at
+    /// the end of the password and at random positions inside it. A count
+    /// above that lower bound is only needed when no placement breaks up the
+    /// common words of the password.
+    ///
+    /// Characters are only replaced when they are forbidden or the password
+    /// is at its maximum length, and characters already added are never
+    /// changed again.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A string slice representing the password typed by the user.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the balanced password and its edits, or
+    /// an `Err` with [`SecurepassError::InvalidPolicy`] if the policy is
+    /// inconsistent, [`SecurepassError::UnsatisfiableRequirements`] if the
+    /// password is too long or could not be fixed in 100 edits, or the error
+    /// of a failed dictionary lookup.
+    pub fn balance_password_minimally(&self, password: &str) -> Result<MinimalBalance, SecurepassError> {
+        self.balance_password_minimally_with_rng(password, &mut thread_rng())
+    }
+
+    /// Balances a password with a few small edits so that it follows the
+    /// policy, using the given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A string slice representing the password typed by the user.
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`PasswordPolicy::balance_password_minimally`].
+    pub fn balance_password_minimally_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        password: &str,
+        rng: &mut R,
+    ) -> Result<MinimalBalance, SecurepassError> {
+        self.check_consistency()?;
+
+        let mut balancer = Balancer {
+            policy: self,
+            chars: normalize(password, Normalization::Nfc).chars().collect(),
+            edits: Vec::new(),
+        };
+        while balancer.edits.len() < MAX_GENERATION_ATTEMPTS {
+            let password: String = balancer.chars.iter().collect();
+            let violations = self.violations(&password)?;
+            let violation = violations.iter().find(|violation| !is_target(violation));
+            match violation.or(violations.first()) {
+                Some(violation) => balancer.fix(violation, &violations, rng)?,
+                None => {
+                    return Ok(MinimalBalance {
+                        password,
+                        edits: balancer.edits,
+                    })
+                }
+            }
+        }
+
+        Err(SecurepassError::UnsatisfiableRequirements(format!(
+            "the password does not follow the policy after {} edits",
+            MAX_GENERATION_ATTEMPTS
+        )))
+    }
+}
+
+impl Balancer<'_> {
+    /// Makes the edits fixing one broken rule.
+    fn fix<R: RngCore + CryptoRng>(
+        &mut self,
+        violation: &PolicyViolation,
+        violations: &[PolicyViolation],
+        rng: &mut R,
+    ) -> Result<(), SecurepassError> {
+        let end = self.chars.len();
+
+        match violation {
+            PolicyViolation::TooShort { .. }
+            | PolicyViolation::EntropyTooLow { .. }
+            | PolicyViolation::StrengthTooLow { .. } => self.reach_targets(rng),
+            PolicyViolation::TooFewCharacters { class, .. } => {
+                let charset: Vec<char> = self.policy.allowed_chars(&class.charset()).chars().collect();
+                self.add(end, &charset, rng)
+            }
+            PolicyViolation::ForbiddenCharacter(forbidden) => {
+                let class = ADDED_CLASSES.into_iter().find(|class| class.contains(*forbidden));
+                let mut charset: Vec<char> = match class {
+                    Some(class) => self.policy.allowed_chars(&class.charset()).chars().collect(),
+                    None => Vec::new(),
+                };
+                if charset.is_empty() {
+                    charset = self.added_charset(&[]);
+                }
+                for position in 0..self.chars.len() {
+                    if self.chars[position] == *forbidden {
+                        self.substitute(position, &charset, rng)?;
+                    }
+                }
+                Ok(())
+            }
+            PolicyViolation::TooManyConsecutiveRepeats {
+                character, max_count, ..
+            } => {
+                let run_start = (0..self.chars.len())
+                    .find(|start| self.chars[*start..].iter().take_while(|c| *c == character).count() > *max_count)
+                    .unwrap_or(0);
+                let charset: Vec<char> =
+                    self.added_charset(violations).into_iter().filter(|c| c != character).collect();
+                self.add(run_start + max_count, &charset, rng)
+            }
+            PolicyViolation::ContainsCommonWord => {
+                let password: String = self.chars.iter().collect();
+                let hits = Dictionary::builtin()?.find(&password);
+                let position = hits
+                    .iter()
+                    .max_by_key(|hit| hit.end - hit.start)
+                    .map_or(end / 2, |hit| (hit.start + hit.end) / 2);
+                let charset = self.added_charset(violations);
+                self.add(position, &charset, rng)
+            }
+            PolicyViolation::CommonPassword => {
+                let charset = self.added_charset(violations);
+                self.add(end / 2, &charset, rng)
+            }
+            PolicyViolation::TooLong { length, max_length } => Err(SecurepassError::UnsatisfiableRequirements(
+                format!("the password has {} characters, more than {}", length, max_length),
+            )),
+            PolicyViolation::DictionaryUnavailable => Err(SecurepassError::UnsatisfiableRequirements(
+                String::from("the common words dictionary is unavailable"),
+            )),
+        }
+    }
+
+    /// Makes the fewest edits reaching the length, entropy and strength
+    /// targets of the policy.
+    fn reach_targets<R: RngCore + CryptoRng>(&mut self, rng: &mut R) -> Result<(), SecurepassError> {
+        let target_entropy = self.target_entropy();
+        let missing = self.missing_classes();
+        let fillers: Vec<char> = ADDED_CLASSES
+            .iter()
+            .flat_map(|class| self.policy.allowed_chars(&class.charset()).chars().collect::<Vec<char>>())
+            .collect();
+        let length = self.chars.len();
+        let room = self.policy.max_length.map_or(usize::MAX, |max_length| max_length.saturating_sub(length));
+        let replaceable = self.replaceable_positions().len();
+        let pool_size = self.pool_size();
+
+        for edit_count in 1..=MAX_GENERATION_ATTEMPTS.saturating_sub(self.edits.len()) {
+            let inserted = edit_count.min(room);
+            let added_classes = &missing[..edit_count.min(missing.len())];
+            let added_pool: usize = added_classes.iter().map(|class| class.charset().chars().count()).sum();
+            let entropy = (length + inserted) as f64 * ((pool_size + added_pool) as f64).log2();
+            let reachable = edit_count - inserted <= replaceable
+                && length + inserted >= self.policy.min_length
+                && entropy >= target_entropy;
+            if !reachable {
+                continue;
+            }
+
+            for attempt in 0..PLACEMENT_ATTEMPTS {
+                let mut candidate = self.clone();
+                for edit in 0..edit_count {
+                    let charset: Vec<char> = match added_classes.get(edit) {
+                        Some(class) => self.policy.allowed_chars(&class.charset()).chars().collect(),
+                        None => fillers.clone(),
+                    };
+                    if edit < inserted {
+                        let position = match attempt {
+                            0 => candidate.chars.len(),
+                            _ => rng.gen_range(0..=candidate.chars.len()),
+                        };
+                        candidate.insert(position, &charset, rng)?;
+                    } else {
+                        let Some(&position) = candidate.replaceable_positions().choose(rng) else {
+                            break;
+                        };
+                        candidate.substitute(position, &charset, rng)?;
+                    }
+                }
+
+                let password: String = candidate.chars.iter().collect();
+                if candidate.edits.len() == self.edits.len() + edit_count
+                    && self.policy.violations(&password)?.is_empty()
+                {
+                    *self = candidate;
+                    return Ok(());
+                }
+            }
+        }
+
+        Err(SecurepassError::UnsatisfiableRequirements(format!(
+            "the password does not follow the policy after {} edits",
+            MAX_GENERATION_ATTEMPTS
+        )))
+    }
+
+    /// Returns the entropy the password needs for the entropy and strength
+    /// targets of the policy, without common words.
+    fn target_entropy(&self) -> f64 {
+        let strength_entropy = match self.policy.min_strength {
+            Some(PasswordStrength::Strong) => MIN_STRONG_ENTROPY,
+            Some(PasswordStrength::Medium) => MIN_MEDIUM_ENTROPY,
+            _ => 0.0,
+        };
+
+        self.policy.min_entropy.unwrap_or(0.0).max(strength_entropy)
+    }
+
+    /// Returns the allowed classes missing from the password, largest first.
+    fn missing_classes(&self) -> Vec<CharacterClass> {
+        let mut missing: Vec<CharacterClass> = ADDED_CLASSES
+            .into_iter()
+            .filter(|class| {
+                !self.chars.iter().any(|c| class.contains(*c))
+                    && !self.policy.allowed_chars(&class.charset()).is_empty()
+            })
+            .collect();
+        missing.sort_by_key(|class| std::cmp::Reverse(class.charset().chars().count()));
+
+        missing
+    }
+
+    /// Returns the pool size the entropy of the password is computed from.
+    fn pool_size(&self) -> usize {
+        let sets = CharacterSets::default();
+        let classes = sets.classes();
+        let present = classes.map(|set| self.chars.iter().any(|c| set.contains(*c)));
+        let mut other: Vec<char> = Vec::new();
+        for c in &self.chars {
+            if !classes.iter().any(|set| set.contains(*c)) && !other.contains(c) {
+                other.push(*c);
+            }
+        }
+
+        sets.pool_size(present) + other.len()
+    }
+
+    /// Returns the allowed characters of the class to add: the first class
+    /// the policy still requires, or else the first class missing from the
+    /// password, or else every allowed character.
+    fn added_charset(&self, violations: &[PolicyViolation]) -> Vec<char> {
+        let required = violations.iter().find_map(|violation| match violation {
+            PolicyViolation::TooFewCharacters { class, .. } => Some(*class),
+            _ => None,
+        });
+        let missing = ADDED_CLASSES.into_iter().find(|class| {
+            !self.chars.iter().any(|c| class.contains(*c))
+                && !self.policy.allowed_chars(&class.charset()).is_empty()
+        });
+
+        match required.or(missing) {
+            Some(class) => self.policy.allowed_chars(&class.charset()).chars().collect(),
+            None => ADDED_CLASSES
+                .iter()
+                .flat_map(|class| self.policy.allowed_chars(&class.charset()).chars().collect::<Vec<char>>())
+                .collect(),
+        }
+    }
+
+    /// Inserts a random character of the charset at the given position, or
+    /// replaces a character when the password is at its maximum length.
+    fn add<R: RngCore + CryptoRng>(
+        &mut self,
+        position: usize,
+        charset: &[char],
+        rng: &mut R,
+    ) -> Result<(), SecurepassError> {
+        if self.policy.max_length.is_some_and(|max_length| self.chars.len() >= max_length) {
+            let replaceable = self.replaceable_positions();
+            let Some(&position) = replaceable.choose(rng) else {
+                return Err(SecurepassError::UnsatisfiableRequirements(String::from(
+                    "no character can be replaced without breaking the policy",
+                )));
+            };
+            return self.substitute(position, charset, rng);
+        }
+
+        self.insert(position, charset, rng)
+    }
+
+    /// Inserts a random character of the charset at the given position.
+    fn insert<R: RngCore + CryptoRng>(
+        &mut self,
+        position: usize,
+        charset: &[char],
+        rng: &mut R,
+    ) -> Result<(), SecurepassError> {
+        let character = *charset.choose(rng).ok_or(SecurepassError::EmptyCharset)?;
+        for edit in &mut self.edits {
+            if edit.position() >= position {
+                *edit.position_mut() += 1;
+            }
+        }
+        self.chars.insert(position, character);
+        self.edits.push(PasswordEdit::Inserted { position, character });
+
+        Ok(())
+    }
+
+    /// Replaces the character at the given position with a random character
+    /// of the charset.
+    fn substitute<R: RngCore + CryptoRng>(
+        &mut self,
+        position: usize,
+        charset: &[char],
+        rng: &mut R,
+    ) -> Result<(), SecurepassError> {
+        let character = *charset.choose(rng).ok_or(SecurepassError::EmptyCharset)?;
+        let previous = std::mem::replace(&mut self.chars[position], character);
+        self.edits.push(PasswordEdit::Substituted {
+            position,
+            previous,
+            character,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the positions of characters that were not added and whose
+    /// class has more characters than the policy requires.
+    fn replaceable_positions(&self) -> Vec<usize> {
+        let minimums = self.policy.class_minimums();
+
+        (0..self.chars.len())
+            .filter(|position| self.edits.iter().all(|edit| edit.position() != *position))
+            .filter(|position| {
+                minimums.iter().all(|(class, min_count)| {
+                    !class.contains(self.chars[*position])
+                        || self.chars.iter().filter(|c| class.contains(**c)).count() > *min_count
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns whether a violation is one of the length, entropy and strength
+/// targets, which are reached together.
+fn is_target(violation: &PolicyViolation) -> bool {
+    matches!(
+        violation,
+        PolicyViolation::TooShort { .. }
+            | PolicyViolation::EntropyTooLow { .. }
+            | PolicyViolation::StrengthTooLow { .. }
+    )
+}
+
+/// Balances a password with a few small edits so that it is strong and has
+/// at least 10 characters.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password typed by the user.
+///
+/// # Returns
+///
+/// The same `Result` as [`PasswordPolicy::balance_password_minimally`].
+pub fn balance_password_minimally(password: &str) -> Result<MinimalBalance, SecurepassError> {
+    balance_password_minimally_with_rng(password, &mut thread_rng())
+}
+
+/// Balances a password with a few small edits so that it is strong and has
+/// at least 10 characters, using the given random number generator.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password typed by the user.
+/// * `rng` - A mutable reference to a cryptographically secure random number generator.
+///
+/// # Returns
+///
+/// The same `Result` as [`PasswordPolicy::balance_password_minimally`].
+pub fn balance_password_minimally_with_rng<R: RngCore + CryptoRng>(
+    password: &str,
+    rng: &mut R,
+) -> Result<MinimalBalance, SecurepassError> {
+    let policy = PasswordPolicy {
+        min_strength: Some(PasswordStrength::Strong),
+        ..Default::default()
+    };
+
+    policy.balance_password_minimally_with_rng(password, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check_password_strength;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    /// Rebuilds the original password by undoing the edits.
+    fn undo(balanced: &MinimalBalance) -> String {
+        let mut chars: Vec<Option<char>> = balanced.password.chars().map(Some).collect();
+        for edit in &balanced.edits {
+            match edit {
+                PasswordEdit::Inserted { position, .. } => chars[*position] = None,
+                PasswordEdit::Substituted { position, previous, .. } => chars[*position] = Some(*previous),
+            }
+        }
+
+        chars.into_iter().flatten().collect()
+    }
+
+    #[test]
+    fn test_balance_password_minimally() {
+        let balanced = balance_password_minimally_with_rng("Sunflower", &mut ChaCha20Rng::seed_from_u64(1)).unwrap();
+
+        assert_eq!(check_password_strength(&balanced.password).unwrap(), PasswordStrength::Strong);
+        assert!(balanced.edits.iter().all(|edit| matches!(edit, PasswordEdit::Inserted { .. })));
+        assert!(balanced.edits.len() <= 6);
+        assert_eq!(undo(&balanced), "Sunflower");
+        for edit in &balanced.edits {
+            assert_eq!(balanced.password.chars().nth(edit.position()), Some(edit.character()));
+        }
+    }
+
+    #[test]
+    fn test_balance_password_minimally_reaches_lower_bound() {
+        let policy = PasswordPolicy {
+            min_length: 14,
+            min_uppercase: 1,
+            min_numbers: 2,
+            min_special_chars: 2,
+            ..Default::default()
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(4);
+
+        for password in ["sunflower", "Sunflower1", "sunflowerfield!", "SUNFLOWERFIELD"] {
+            let chars: Vec<char> = password.chars().collect();
+            let length_deficit = policy.min_length.saturating_sub(chars.len());
+            let class_deficit: usize = policy
+                .class_minimums()
+                .iter()
+                .map(|(class, min_count)| {
+                    min_count.saturating_sub(chars.iter().filter(|c| class.contains(**c)).count())
+                })
+                .sum();
+
+            let balanced = policy.balance_password_minimally_with_rng(password, &mut rng).unwrap();
+            assert_eq!(policy.validate(&balanced.password), Ok(()));
+            assert_eq!(
+                balanced.edits.len(),
+                length_deficit.max(class_deficit),
+                "{} -> {}",
+                password,
+                balanced.password
+            );
+        }
+    }
+
+    /// Returns the fewest characters to append so that the entropy of the
+    /// password reaches the target, adding the largest missing classes first.
+    fn fewest_appended(password: &str, target_entropy: f64) -> usize {
+        (0..)
+            .find(|count| {
+                let mut extended = String::from(password);
+                let mut added = [
+                    (CharacterClass::Uppercase, 'A'),
+                    (CharacterClass::Lowercase, 'a'),
+                    (CharacterClass::Special, '!'),
+                    (CharacterClass::Number, '1'),
+                ]
+                .into_iter()
+                .filter(|(class, _)| !password.chars().any(|c| class.contains(c)))
+                .map(|(_, added)| added);
+                for _ in 0..*count {
+                    extended.push(added.next().unwrap_or('a'));
+                }
+                crate::calculate_entropy(&extended) >= target_entropy
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_balance_password_minimally_reaches_entropy_lower_bound() {
+        let mut rng = ChaCha20Rng::seed_from_u64(5);
+
+        for password in ["password", "sunflower", "Sunflower", "xqzvbkwj", "Xqzvb7kw"] {
+            let balanced = balance_password_minimally_with_rng(password, &mut rng).unwrap();
+            assert_eq!(check_password_strength(&balanced.password).unwrap(), PasswordStrength::Strong);
+            assert_eq!(undo(&balanced), password);
+            assert_eq!(
+                balanced.edits.len(),
+                fewest_appended(password, MIN_STRONG_ENTROPY),
+                "{} -> {}",
+                password,
+                balanced.password
+            );
+        }
+
+        let policy = PasswordPolicy {
+            min_entropy: Some(70.0),
+            ..Default::default()
+        };
+        for password in ["xqzvbkwj", "sunflower"] {
+            let balanced = policy.balance_password_minimally_with_rng(password, &mut rng).unwrap();
+            assert_eq!(policy.validate(&balanced.password), Ok(()));
+            assert_eq!(balanced.edits.len(), fewest_appended(password, 70.0));
+        }
+    }
+
+    #[test]
+    fn test_balance_strong_password_minimally() {
+        let balanced = balance_password_minimally("!QEa4Kta2}wg1").unwrap();
+        assert_eq!(balanced.password, "!QEa4Kta2}wg1");
+        assert!(balanced.edits.is_empty());
+    }
+
+    #[test]
+    fn test_balance_password_minimally_with_policy() {
+        let policy = PasswordPolicy {
+            min_numbers: 2,
+            min_special_chars: 1,
+            forbidden_chars: String::from("o"),
+            max_consecutive_repeats: Some(2),
+            ..Default::default()
+        };
+
+        let balanced = policy
+            .balance_password_minimally_with_rng("mooonlight", &mut ChaCha20Rng::seed_from_u64(2))
+            .unwrap();
+        assert_eq!(policy.validate(&balanced.password), Ok(()));
+        assert_eq!(undo(&balanced), "mooonlight");
+        let substituted = balanced.edits.iter().filter(|edit| matches!(edit, PasswordEdit::Substituted { .. }));
+        assert_eq!(substituted.count(), 3);
+    }
+
+    #[test]
+    fn test_balance_password_minimally_at_max_length() {
+        let policy = PasswordPolicy {
+            max_length: Some(10),
+            min_numbers: 1,
+            ..Default::default()
+        };
+
+        let balanced = policy
+            .balance_password_minimally_with_rng("abcdefghij", &mut ChaCha20Rng::seed_from_u64(3))
+            .unwrap();
+        assert_eq!(balanced.password.chars().count(), 10);
+        assert!(matches!(balanced.edits[..], [PasswordEdit::Substituted { .. }]));
+
+        let result = policy.balance_password_minimally("abcdefghijk");
+        assert!(matches!(result, Err(SecurepassError::UnsatisfiableRequirements(_))));
+    }
+}
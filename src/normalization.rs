@@ -0,0 +1,68 @@
+//! Unicode normalization of passwords before they are measured.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Enum representing the Unicode normalization forms applied to passwords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalization {
+    /// Canonical composition, so "e" followed by a combining acute accent
+    /// becomes the single character "é".
+    #[default]
+    Nfc,
+    /// Compatibility composition, which also replaces ligatures, fullwidth
+    /// letters and superscripts with plain characters, so "ﬁ" becomes "fi".
+    Nfkc,
+}
+
+/// Normalizes a text to the given form.
+///
+/// # Arguments
+///
+/// * `text` - A string slice representing the text, like a password or phrase.
+/// * `form` - The normalization form.
+///
+/// # Returns
+///
+/// The normalized text.
+pub fn normalize(text: &str, form: Normalization) -> String {
+    match form {
+        Normalization::Nfc => text.nfc().collect(),
+        Normalization::Nfkc => text.nfkc().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_nfc() {
+        assert_eq!(normalize("e\u{301}", Normalization::Nfc), "\u{e9}");
+        assert_eq!(normalize("zaz\u{307}o\u{301}\u{142}c\u{301}", Normalization::Nfc), "za\u{17c}\u{f3}\u{142}\u{107}");
+        assert_eq!(normalize("A\u{30a}\u{301}", Normalization::Nfc), "\u{1fa}");
+        assert_eq!(normalize("\u{1fa}", Normalization::Nfc), "\u{1fa}");
+        assert_eq!(normalize("\u{fb01}", Normalization::Nfc), "\u{fb01}");
+        assert_eq!(normalize("Password1!", Normalization::Nfc), "Password1!");
+    }
+
+    #[test]
+    fn test_normalize_other_scripts() {
+        assert_eq!(normalize("\u{3b1}\u{301}", Normalization::Nfc), "\u{3ac}");
+        assert_eq!(normalize("\u{438}\u{306}", Normalization::Nfc), "\u{439}");
+        assert_eq!(normalize("\u{1100}\u{1161}\u{11a8}", Normalization::Nfc), "\u{ac01}");
+        assert_eq!(normalize("\u{ac01}", Normalization::Nfc), "\u{ac01}");
+    }
+
+    #[test]
+    fn test_normalize_reorders_marks() {
+        assert_eq!(normalize("e\u{302}\u{323}", Normalization::Nfc), "\u{1ec7}");
+        assert_eq!(normalize("e\u{323}\u{302}", Normalization::Nfc), "\u{1ec7}");
+        assert_eq!(normalize("a\u{301}\u{301}", Normalization::Nfc), "\u{e1}\u{301}");
+    }
+
+    #[test]
+    fn test_normalize_nfkc() {
+        assert_eq!(normalize("\u{fb01}\u{ff30}\u{bd}\u{2163}", Normalization::Nfkc), "fiP1\u{2044}2IV");
+        assert_eq!(normalize("\u{ff45}\u{301}", Normalization::Nfkc), "\u{e9}");
+    }
+}
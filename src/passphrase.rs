@@ -0,0 +1,284 @@
+//! Diceware-style passphrase generation based on the bundled word list.
+
+use crate::dictionary::passphrase_words;
+use crate::{generate_random_password_with_rng, SecurepassError, NUMBERS, SPECIAL_CHARSET};
+use rand::{thread_rng, CryptoRng, Rng, RngCore};
+
+const MIN_WORD_COUNT: usize = 4;
+
+/// Structure representing the options for passphrase generation.
+pub struct PassphraseOptions {
+    /// Number of words in the passphrase.
+    pub word_count: usize,
+    /// Separator placed between the words.
+    pub separator: String,
+    /// Whether to capitalize the first letter of every word.
+    pub capitalize: bool,
+    /// Whether to append a random number to one of the words.
+    pub include_number: bool,
+    /// Whether to append a random special character to one of the words.
+    pub include_special_char: bool,
+}
+
+impl Default for PassphraseOptions {
+    /// Returns the default passphrase options.
+    fn default() -> Self {
+        Self {
+            word_count: 6,
+            separator: String::from("-"),
+            capitalize: true,
+            include_number: true,
+            include_special_char: false,
+        }
+    }
+}
+
+impl PassphraseOptions {
+    /// Generates a passphrase based on the specified options.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the generated passphrase if successful,
+    /// or an `Err` with [`SecurepassError::TooFewWords`] if fewer than 4 words
+    /// were requested, [`SecurepassError::EmptyCharset`] if the separator
+    /// contains every character that can be injected, or
+    /// [`SecurepassError::DictionaryIo`] if the word list cannot be read.
+    pub fn generate_passphrase(&self) -> Result<String, SecurepassError> {
+        self.generate_passphrase_with_rng(&mut thread_rng())
+    }
+
+    /// Generates a passphrase based on the specified options using the given
+    /// random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`PassphraseOptions::generate_passphrase`].
+    pub fn generate_passphrase_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<String, SecurepassError> {
+        if self.word_count < MIN_WORD_COUNT {
+            return Err(SecurepassError::TooFewWords {
+                word_count: self.word_count,
+                min_word_count: MIN_WORD_COUNT,
+            });
+        }
+        let numbers = injected_charset(NUMBERS, &self.separator);
+        let special_chars = injected_charset(SPECIAL_CHARSET, &self.separator);
+        let words = &passphrase_words()?.words;
+
+        let mut picked: Vec<String> = (0..self.word_count)
+            .map(|_| words[rng.gen_range(0..words.len())].clone())
+            .collect();
+
+        if self.capitalize {
+            picked = picked.iter().map(|word| capitalize(word)).collect();
+        }
+        if self.include_number {
+            let index = rng.gen_range(0..picked.len());
+            picked[index].push_str(&generate_random_password_with_rng(&numbers, 1, rng)?);
+        }
+        if self.include_special_char {
+            let index = rng.gen_range(0..picked.len());
+            picked[index].push_str(&generate_random_password_with_rng(&special_chars, 1, rng)?);
+        }
+
+        Ok(picked.join(&self.separator))
+    }
+
+    /// Calculates the entropy of passphrases generated with these options.
+    ///
+    /// Unlike [`crate::calculate_entropy`], every word counts as a single
+    /// symbol drawn from the loaded word list, and each injected character
+    /// adds the bits of its value and of the word it was appended to.
+    /// Characters of the separator are never injected, so they do not count.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the entropy in bits, or an `Err` with
+    /// [`SecurepassError::DictionaryIo`] if the word list cannot be read, or
+    /// [`SecurepassError::EmptyCharset`] if the separator contains every
+    /// character that can be injected.
+    pub fn calculate_entropy(&self) -> Result<f64, SecurepassError> {
+        let words = passphrase_words()?.words.len() as f64;
+        if self.word_count == 0 || words == 0.0 {
+            return Ok(0.0);
+        }
+        let word_count = self.word_count as f64;
+        let mut entropy = word_count * words.log2();
+
+        for (charset, included) in [(NUMBERS, self.include_number), (SPECIAL_CHARSET, self.include_special_char)] {
+            if !included {
+                continue;
+            }
+            let injected = injected_charset(charset, &self.separator).chars().count();
+            if injected == 0 {
+                return Err(SecurepassError::EmptyCharset);
+            }
+            entropy += (injected as f64 * word_count).log2();
+        }
+
+        Ok(entropy)
+    }
+}
+
+/// Returns the characters of a charset that are not in the separator, so an
+/// injected character never merges into a separator.
+///
+/// # Arguments
+///
+/// * `charset` - A string slice representing the characters to inject.
+/// * `separator` - A string slice representing the separator of the words.
+///
+/// # Returns
+///
+/// The injectable characters as a string.
+pub(crate) fn injected_charset(charset: &str, separator: &str) -> String {
+    charset.chars().filter(|c| !separator.contains(*c)).collect()
+}
+
+/// Uppercases the first character of a word.
+///
+/// # Arguments
+///
+/// * `word` - A string slice representing the word.
+///
+/// # Returns
+///
+/// The capitalized word as a string.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PasswordOptions;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_word_list_has_unique_dice_words() {
+        let mut words = passphrase_words().unwrap().words.clone();
+        assert_eq!(words.len(), 1296);
+        words.sort();
+        words.dedup();
+        assert_eq!(words.len(), 1296);
+    }
+
+    #[test]
+    fn test_generate_passphrase() {
+        let options = PassphraseOptions {
+            word_count: 5,
+            separator: String::from(" "),
+            capitalize: false,
+            include_number: false,
+            include_special_char: false,
+        };
+        let words = passphrase_words().unwrap();
+
+        let passphrase = options.generate_passphrase().unwrap();
+        let picked: Vec<&str> = passphrase.split(' ').collect();
+        assert_eq!(picked.len(), 5);
+        assert!(picked.iter().all(|word| words.words.iter().any(|w| w == word)));
+    }
+
+    #[test]
+    fn test_generate_passphrase_with_injections() {
+        let options = PassphraseOptions {
+            include_special_char: true,
+            ..Default::default()
+        };
+
+        for _ in 0..200 {
+            let passphrase = options.generate_passphrase().unwrap();
+            let words: Vec<&str> = passphrase.split('-').collect();
+            assert_eq!(words.len(), 6, "{}", passphrase);
+            assert!(words.iter().all(|word| !word.is_empty()));
+            assert!(passphrase.chars().any(|c| c.is_ascii_uppercase()));
+            assert!(passphrase.chars().any(|c| NUMBERS.contains(c)));
+            assert!(passphrase.chars().any(|c| SPECIAL_CHARSET.contains(c) && c != '-'));
+        }
+    }
+
+    #[test]
+    fn test_separator_is_never_injected() {
+        let options = PassphraseOptions {
+            separator: String::from("#1"),
+            include_special_char: true,
+            ..Default::default()
+        };
+        assert_eq!(injected_charset(NUMBERS, &options.separator), "023456789");
+
+        let passphrase = options.generate_passphrase().unwrap();
+        assert_eq!(passphrase.split("#1").count(), 6);
+        assert!(!passphrase.replace("#1", "").contains(['#', '1']));
+
+        let options = PassphraseOptions {
+            separator: String::from(NUMBERS),
+            ..Default::default()
+        };
+        assert!(matches!(options.generate_passphrase(), Err(SecurepassError::EmptyCharset)));
+    }
+
+    #[test]
+    fn test_generate_passphrase_with_seeded_rng() {
+        let options = PassphraseOptions::default();
+
+        let first = options.generate_passphrase_with_rng(&mut ChaCha20Rng::seed_from_u64(1)).unwrap();
+        let second = options.generate_passphrase_with_rng(&mut ChaCha20Rng::seed_from_u64(1)).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_generate_passphrase_too_few_words() {
+        let options = PassphraseOptions {
+            word_count: 3,
+            ..Default::default()
+        };
+
+        let result = options.generate_passphrase();
+        assert!(matches!(
+            result,
+            Err(SecurepassError::TooFewWords { word_count: 3, min_word_count: 4 })
+        ));
+    }
+
+    #[test]
+    fn test_calculate_passphrase_entropy() {
+        let options = PassphraseOptions {
+            include_number: false,
+            ..Default::default()
+        };
+        let words = passphrase_words().unwrap().words.len() as f64;
+        assert!((options.calculate_entropy().unwrap() - 6.0 * words.log2()).abs() < 1e-9);
+
+        let with_number = PassphraseOptions::default();
+        assert!(with_number.calculate_entropy().unwrap() > options.calculate_entropy().unwrap());
+
+        let numbers_separator = PassphraseOptions {
+            separator: String::from(NUMBERS),
+            ..Default::default()
+        };
+        assert!(matches!(numbers_separator.calculate_entropy(), Err(SecurepassError::EmptyCharset)));
+    }
+
+    #[test]
+    fn test_generate_password_with_passphrase() {
+        let options = PasswordOptions {
+            passphrase: Some(PassphraseOptions::default()),
+            ..Default::default()
+        };
+
+        let password = options.generate_password().unwrap();
+        assert_eq!(password.split('-').count(), 6);
+    }
+}
@@ -0,0 +1,299 @@
+//! Generation of passwords and codes following a pattern, like `Cvccvc-99-!`.
+//!
+//! Every alphanumeric character and `!`, `*` of a pattern is a placeholder:
+//!
+//! | Placeholder | Characters                            |
+//! |-------------|---------------------------------------|
+//! | `l` / `L`   | lowercase / uppercase letter          |
+//! | `a`         | letter of either case                 |
+//! | `c` / `C`   | lowercase / uppercase consonant       |
+//! | `v` / `V`   | lowercase / uppercase vowel           |
+//! | `9`         | digit                                 |
+//! | `x` / `X`   | lowercase / uppercase letter or digit |
+//! | `!`         | special character                     |
+//! | `*`         | any of the above                      |
+//!
+//! Any other character is copied as is, and `\` copies the next character
+//! as is, so `\9` is a literal nine.
+
+use crate::{
+    Charset, SecurepassError, LOWERCASE_CHARSET, NUMBERS, SPECIAL_CHARSET,
+    UPPERCASE_CHARSET,
+};
+use rand::{thread_rng, CryptoRng, RngCore};
+use std::error::Error;
+use std::fmt;
+
+const VOWELS: &str = "aeiou";
+const ESCAPE: char = '\\';
+
+/// Enum representing the reasons a pattern is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// The pattern has no character.
+    Empty,
+    /// An alphanumeric character that is not a placeholder was not escaped.
+    UnknownPlaceholder {
+        /// The unknown placeholder.
+        placeholder: char,
+        /// Index of the placeholder in the pattern, in characters.
+        position: usize,
+    },
+    /// The pattern ends with an escape character.
+    DanglingEscape {
+        /// Index of the escape character in the pattern, in characters.
+        position: usize,
+    },
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "pattern is empty"),
+            Self::UnknownPlaceholder { placeholder, position } => {
+                write!(f, "unknown placeholder '{}' at position {}", placeholder, position)
+            }
+            Self::DanglingEscape { position } => {
+                write!(f, "escape at position {} is not followed by a character", position)
+            }
+        }
+    }
+}
+
+impl Error for PatternError {}
+
+/// Enum representing a character class a placeholder stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    Lowercase,
+    Uppercase,
+    Letter,
+    LowercaseConsonant,
+    UppercaseConsonant,
+    LowercaseVowel,
+    UppercaseVowel,
+    Number,
+    LowercaseAlphanumeric,
+    UppercaseAlphanumeric,
+    Special,
+    Any,
+}
+
+impl Placeholder {
+    /// Returns the placeholder written as `c`, if any.
+    fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            'l' => Self::Lowercase,
+            'L' => Self::Uppercase,
+            'a' => Self::Letter,
+            'c' => Self::LowercaseConsonant,
+            'C' => Self::UppercaseConsonant,
+            'v' => Self::LowercaseVowel,
+            'V' => Self::UppercaseVowel,
+            '9' => Self::Number,
+            'x' => Self::LowercaseAlphanumeric,
+            'X' => Self::UppercaseAlphanumeric,
+            '!' => Self::Special,
+            '*' => Self::Any,
+            _ => return None,
+        })
+    }
+
+    /// Returns the characters the placeholder stands for.
+    fn charset(self) -> String {
+        let consonants = LOWERCASE_CHARSET.chars().filter(|c| !VOWELS.contains(*c));
+
+        match self {
+            Self::Lowercase => String::from(LOWERCASE_CHARSET),
+            Self::Uppercase => String::from(UPPERCASE_CHARSET),
+            Self::Letter => [LOWERCASE_CHARSET, UPPERCASE_CHARSET].concat(),
+            Self::LowercaseConsonant => consonants.collect(),
+            Self::UppercaseConsonant => consonants.map(|c| c.to_ascii_uppercase()).collect(),
+            Self::LowercaseVowel => String::from(VOWELS),
+            Self::UppercaseVowel => VOWELS.to_ascii_uppercase(),
+            Self::Number => String::from(NUMBERS),
+            Self::LowercaseAlphanumeric => [LOWERCASE_CHARSET, NUMBERS].concat(),
+            Self::UppercaseAlphanumeric => [UPPERCASE_CHARSET, NUMBERS].concat(),
+            Self::Special => String::from(SPECIAL_CHARSET),
+            Self::Any => [LOWERCASE_CHARSET, UPPERCASE_CHARSET, NUMBERS, SPECIAL_CHARSET].concat(),
+        }
+    }
+}
+
+/// Enum representing one character of a parsed pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternToken {
+    /// A character copied as is.
+    Literal(char),
+    /// A character drawn from a set, compiled when the pattern is parsed.
+    Class(Charset),
+}
+
+/// Structure representing a parsed pattern that generates passwords of an
+/// exact shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPattern {
+    tokens: Vec<PatternToken>,
+}
+
+impl PasswordPattern {
+    /// Parses a pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - A string slice with the pattern, like `XXXX-XXXX-XXXX`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the parsed `PasswordPattern`, or an
+    /// `Err` with [`SecurepassError::InvalidPattern`] describing the first
+    /// problem found.
+    pub fn parse(pattern: &str) -> Result<Self, SecurepassError> {
+        let mut tokens = Vec::new();
+        let mut chars = pattern.chars().enumerate();
+
+        while let Some((position, c)) = chars.next() {
+            let token = if c == ESCAPE {
+                match chars.next() {
+                    Some((_, escaped)) => PatternToken::Literal(escaped),
+                    None => return Err(SecurepassError::InvalidPattern(PatternError::DanglingEscape { position })),
+                }
+            } else if let Some(placeholder) = Placeholder::from_char(c) {
+                PatternToken::Class(Charset::new(&placeholder.charset()))
+            } else if c.is_alphanumeric() {
+                return Err(SecurepassError::InvalidPattern(PatternError::UnknownPlaceholder {
+                    placeholder: c,
+                    position,
+                }));
+            } else {
+                PatternToken::Literal(c)
+            };
+            tokens.push(token);
+        }
+        if tokens.is_empty() {
+            return Err(SecurepassError::InvalidPattern(PatternError::Empty));
+        }
+
+        Ok(Self { tokens })
+    }
+
+    /// Generates a password following the pattern.
+    ///
+    /// # Returns
+    ///
+    /// The generated password as a string.
+    pub fn generate(&self) -> String {
+        self.generate_with_rng(&mut thread_rng())
+    }
+
+    /// Generates a password following the pattern using the given random
+    /// number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The generated password as a string.
+    pub fn generate_with_rng<R: RngCore + CryptoRng>(&self, rng: &mut R) -> String {
+        self.tokens
+            .iter()
+            .filter_map(|token| match token {
+                PatternToken::Literal(c) => Some(*c),
+                PatternToken::Class(charset) => charset.sample(rng),
+            })
+            .collect()
+    }
+
+    /// Calculates the exact entropy of passwords generated from the pattern.
+    ///
+    /// Every placeholder adds the bits of a uniform pick from its characters,
+    /// and literal characters add nothing.
+    ///
+    /// # Returns
+    ///
+    /// The entropy in bits as a `f64`.
+    pub fn calculate_entropy(&self) -> f64 {
+        self.tokens
+            .iter()
+            .map(|token| match token {
+                PatternToken::Literal(_) => 0.0,
+                PatternToken::Class(charset) => charset.bits_per_char(),
+            })
+            .sum()
+    }
+
+    /// Returns the number of characters of the generated passwords.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns `true` if the pattern has no character, which parsing never allows.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_generate_from_pattern() {
+        let pattern = PasswordPattern::parse("Cvccvc-99-!").unwrap();
+        assert_eq!(pattern.len(), 11);
+
+        let password = pattern.generate_with_rng(&mut ChaCha20Rng::seed_from_u64(2));
+        let chars: Vec<char> = password.chars().collect();
+        assert_eq!(chars.len(), 11);
+        assert!(chars[0].is_ascii_uppercase() && !"AEIOU".contains(chars[0]));
+        assert!(VOWELS.contains(chars[1]) && VOWELS.contains(chars[4]));
+        assert!(chars[2].is_ascii_lowercase() && !VOWELS.contains(chars[2]));
+        assert_eq!((chars[6], chars[9]), ('-', '-'));
+        assert!(chars[7].is_ascii_digit() && chars[8].is_ascii_digit());
+        assert!(SPECIAL_CHARSET.contains(chars[10]));
+    }
+
+    #[test]
+    fn test_pattern_entropy() {
+        let pattern = PasswordPattern::parse("XXXX-XXXX-XXXX").unwrap();
+        assert!((pattern.calculate_entropy() - 12.0 * 36f64.log2()).abs() < 1e-9);
+
+        let pattern = PasswordPattern::parse("Cv-99").unwrap();
+        assert!((pattern.calculate_entropy() - (21f64 * 5.0 * 100.0).log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pattern_escapes() {
+        let pattern = PasswordPattern::parse("\\A\\9\\\\9").unwrap();
+        let password = pattern.generate();
+        assert!(password.starts_with("A9\\"));
+        assert!((pattern.calculate_entropy() - 10f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        assert!(matches!(
+            PasswordPattern::parse(""),
+            Err(SecurepassError::InvalidPattern(PatternError::Empty))
+        ));
+        assert!(matches!(
+            PasswordPattern::parse("99-q"),
+            Err(SecurepassError::InvalidPattern(PatternError::UnknownPlaceholder { placeholder: 'q', position: 3 }))
+        ));
+        assert!(matches!(
+            PasswordPattern::parse("99\\"),
+            Err(SecurepassError::InvalidPattern(PatternError::DanglingEscape { position: 2 }))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_pattern_error_source() {
+        let error = PasswordPattern::parse("b").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid pattern: unknown placeholder 'b' at position 0");
+        assert!(Error::source(&error).is_some());
+    }
+}
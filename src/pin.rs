@@ -0,0 +1,254 @@
+//! Numeric PIN generation that avoids easily guessed PINs.
+
+use crate::dictionary::common_pins;
+use crate::{
+    generate_random_password_with_rng, PasswordStrength, SecurepassError, MAX_GENERATION_ATTEMPTS, NUMBERS,
+};
+use rand::{thread_rng, CryptoRng, RngCore};
+
+const MIN_PIN_LENGTH: usize = 4;
+const MAX_PIN_LENGTH: usize = 8;
+/// Length from which a PIN without weaknesses is strong.
+const STRONG_PIN_LENGTH: usize = 6;
+/// Number of consecutive equal digits that makes a PIN weak.
+const MAX_SAME_DIGITS: usize = 3;
+/// Number of digits in an ascending or descending run that makes a PIN weak.
+const MIN_SEQUENCE_LENGTH: usize = 4;
+
+/// Enum representing why a PIN is easy to guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinWeakness {
+    /// The PIN contains characters other than digits, or no digit at all.
+    NotNumeric,
+    /// The PIN is in the list of the most common PINs.
+    CommonPin,
+    /// The PIN repeats a digit three times in a row, or is a repeated block
+    /// like "1212" or "123123".
+    RepeatedDigits,
+    /// The PIN contains an ascending or descending run like "1234" or "9876".
+    Sequence,
+    /// The PIN looks like a date or a year, like "0512", "1987" or "19870512".
+    Date,
+}
+
+/// Structure representing the options for PIN generation.
+pub struct PinOptions {
+    /// Number of digits of the PIN, from 4 to 8.
+    pub length: usize,
+}
+
+impl Default for PinOptions {
+    /// Returns the default PIN options.
+    fn default() -> Self {
+        Self { length: 6 }
+    }
+}
+
+impl PinOptions {
+    /// Generates a PIN without any [`PinWeakness`].
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the generated PIN if successful, or an
+    /// `Err` with [`SecurepassError::InvalidPinLength`] if the length is not
+    /// between 4 and 8, or the error of a failed common PIN lookup.
+    pub fn generate_pin(&self) -> Result<String, SecurepassError> {
+        self.generate_pin_with_rng(&mut thread_rng())
+    }
+
+    /// Generates a PIN without any [`PinWeakness`] using the given random
+    /// number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`PinOptions::generate_pin`].
+    pub fn generate_pin_with_rng<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<String, SecurepassError> {
+        if !(MIN_PIN_LENGTH..=MAX_PIN_LENGTH).contains(&self.length) {
+            return Err(SecurepassError::InvalidPinLength {
+                length: self.length,
+                min_length: MIN_PIN_LENGTH,
+                max_length: MAX_PIN_LENGTH,
+            });
+        }
+
+        for _ in 0..MAX_GENERATION_ATTEMPTS {
+            let pin = generate_random_password_with_rng(NUMBERS, self.length, rng)?;
+            if check_pin_weakness(&pin)?.is_none() {
+                return Ok(pin);
+            }
+        }
+
+        Err(SecurepassError::UnsatisfiableRequirements(format!(
+            "no PIN without weaknesses was found in {} attempts",
+            MAX_GENERATION_ATTEMPTS
+        )))
+    }
+}
+
+/// Finds why a PIN is easy to guess.
+///
+/// # Arguments
+///
+/// * `pin` - A string slice representing the PIN.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with the first weakness found, or `None` if the
+/// PIN has none, or an `Err` if the common PIN list cannot be read.
+pub fn check_pin_weakness(pin: &str) -> Result<Option<PinWeakness>, SecurepassError> {
+    if pin.is_empty() || !pin.chars().all(|c| NUMBERS.contains(c)) {
+        return Ok(Some(PinWeakness::NotNumeric));
+    }
+    if common_pins()?.ranks.contains_key(pin) {
+        return Ok(Some(PinWeakness::CommonPin));
+    }
+
+    let digits: Vec<u32> = pin.chars().filter_map(|c| c.to_digit(10)).collect();
+    Ok(if has_repeated_digits(&digits) {
+        Some(PinWeakness::RepeatedDigits)
+    } else if has_sequence(&digits) {
+        Some(PinWeakness::Sequence)
+    } else if looks_like_date(&digits) {
+        Some(PinWeakness::Date)
+    } else {
+        None
+    })
+}
+
+/// Checks the strength of a PIN.
+///
+/// A PIN with any [`PinWeakness`] or fewer than 4 digits is weak, one with 4
+/// or 5 digits is medium, and a longer one is strong.
+///
+/// # Arguments
+///
+/// * `pin` - A string slice representing the PIN.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with the PIN strength as a `PasswordStrength`
+/// enum, or an `Err` if the common PIN list cannot be read.
+pub fn check_pin_strength(pin: &str) -> Result<PasswordStrength, SecurepassError> {
+    let length = pin.chars().count();
+
+    Ok(if length < MIN_PIN_LENGTH || check_pin_weakness(pin)?.is_some() {
+        PasswordStrength::Weak
+    } else if length < STRONG_PIN_LENGTH {
+        PasswordStrength::Medium
+    } else {
+        PasswordStrength::Strong
+    })
+}
+
+/// Checks for a digit repeated three times in a row, or a repeated block.
+fn has_repeated_digits(digits: &[u32]) -> bool {
+    let same_run = digits
+        .windows(MAX_SAME_DIGITS)
+        .any(|window| window.iter().all(|digit| *digit == window[0]));
+    let repeated_block = (1..digits.len())
+        .filter(|period| digits.len().is_multiple_of(*period))
+        .any(|period| digits.iter().enumerate().all(|(i, digit)| *digit == digits[i % period]));
+
+    same_run || repeated_block
+}
+
+/// Checks for an ascending or descending run of at least four digits.
+fn has_sequence(digits: &[u32]) -> bool {
+    digits.windows(MIN_SEQUENCE_LENGTH).any(|window| {
+        let steps: Vec<i64> = window.windows(2).map(|pair| pair[1] as i64 - pair[0] as i64).collect();
+        steps.iter().all(|step| *step == 1) || steps.iter().all(|step| *step == -1)
+    })
+}
+
+/// Checks whether the digits read as a year or a day and month, in the
+/// common orders for the length of the PIN.
+fn looks_like_date(digits: &[u32]) -> bool {
+    let number = |range: std::ops::Range<usize>| digits[range].iter().fold(0, |value, digit| value * 10 + digit);
+    let is_day_month = |day: u32, month: u32| (1..=12).contains(&month) && (1..=days_in_month(month)).contains(&day);
+    let is_year = |year: u32| (1900..=2099).contains(&year);
+
+    match digits.len() {
+        4 => {
+            is_year(number(0..4))
+                || is_day_month(number(0..2), number(2..4))
+                || is_day_month(number(2..4), number(0..2))
+        }
+        6 => {
+            is_day_month(number(0..2), number(2..4))
+                || is_day_month(number(2..4), number(0..2))
+                || is_day_month(number(4..6), number(2..4))
+        }
+        8 => {
+            (is_year(number(4..8))
+                && (is_day_month(number(0..2), number(2..4)) || is_day_month(number(2..4), number(0..2))))
+                || (is_year(number(0..4)) && is_day_month(number(6..8), number(4..6)))
+        }
+        _ => false,
+    }
+}
+
+/// Returns the maximum number of days of a month, counting February 29.
+fn days_in_month(month: u32) -> u32 {
+    match month {
+        2 => 29,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_generate_pin() {
+        let mut rng = ChaCha20Rng::seed_from_u64(11);
+        for length in MIN_PIN_LENGTH..=MAX_PIN_LENGTH {
+            let pin = PinOptions { length }.generate_pin_with_rng(&mut rng).unwrap();
+            assert_eq!(pin.len(), length);
+            assert_eq!(check_pin_weakness(&pin).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_generate_pin_invalid_length() {
+        for length in [3, 9] {
+            let result = PinOptions { length }.generate_pin();
+            assert!(matches!(
+                result,
+                Err(SecurepassError::InvalidPinLength { min_length: 4, max_length: 8, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_check_pin_weakness() {
+        assert_eq!(check_pin_weakness("12a4").unwrap(), Some(PinWeakness::NotNumeric));
+        assert_eq!(check_pin_weakness("").unwrap(), Some(PinWeakness::NotNumeric));
+        assert_eq!(check_pin_weakness("2580").unwrap(), Some(PinWeakness::CommonPin));
+        assert_eq!(check_pin_weakness("4777").unwrap(), Some(PinWeakness::RepeatedDigits));
+        assert_eq!(check_pin_weakness("493493").unwrap(), Some(PinWeakness::RepeatedDigits));
+        assert_eq!(check_pin_weakness("93456").unwrap(), Some(PinWeakness::Sequence));
+        assert_eq!(check_pin_weakness("8765").unwrap(), Some(PinWeakness::Sequence));
+        assert_eq!(check_pin_weakness("1987").unwrap(), Some(PinWeakness::Date));
+        assert_eq!(check_pin_weakness("3112").unwrap(), Some(PinWeakness::Date));
+        assert_eq!(check_pin_weakness("120587").unwrap(), Some(PinWeakness::Date));
+        assert_eq!(check_pin_weakness("19870512").unwrap(), Some(PinWeakness::Date));
+        assert_eq!(check_pin_weakness("3190").unwrap(), None);
+        assert_eq!(check_pin_weakness("739184").unwrap(), None);
+    }
+
+    #[test]
+    fn test_check_pin_strength() {
+        assert_eq!(check_pin_strength("1234").unwrap(), PasswordStrength::Weak);
+        assert_eq!(check_pin_strength("739").unwrap(), PasswordStrength::Weak);
+        assert_eq!(check_pin_strength("3190").unwrap(), PasswordStrength::Medium);
+        assert_eq!(check_pin_strength("739184").unwrap(), PasswordStrength::Strong);
+    }
+}
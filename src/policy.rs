@@ -0,0 +1,739 @@
+//! Declarative password policies and generation of compliant passwords.
+
+use crate::{
+    check_has_common_words, find_common_words, normalize, Normalization, check_is_common_password,
+    strength_from_entropy, generate_password_with_required_chars, CharacterSets, Charset, Dictionary,
+    PasswordOptions, PasswordSpecification, PasswordStrength, SecurepassError, LOWERCASE_CHARSET, MAX_GENERATION_ATTEMPTS, MIN_PASSWORD_LENGTH, NUMBERS, SPECIAL_CHARSET,
+    UPPERCASE_CHARSET,
+};
+use rand::{thread_rng, CryptoRng, RngCore};
+
+/// Enum representing a class of characters counted by a policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharacterClass {
+    /// Lowercase letters.
+    Lowercase,
+    /// Uppercase letters.
+    Uppercase,
+    /// Digits.
+    Number,
+    /// Special characters.
+    Special,
+    /// Letters of either case.
+    Letter,
+}
+
+impl CharacterClass {
+    /// Returns whether a character belongs to the class, with the default
+    /// character sets.
+    pub fn contains(&self, c: char) -> bool {
+        self.contains_in_sets(c, DEFAULT_CLASSES)
+    }
+
+    /// Returns whether a character belongs to the class, with the digits and
+    /// special characters of the given character sets. Letters of every
+    /// script count for their case, as well as the letters of the sets.
+    ///
+    /// # Arguments
+    ///
+    /// * `c` - The character.
+    /// * `sets` - The character sets defining the classes.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the character belongs to the class, `false` otherwise.
+    pub fn contains_in(&self, c: char, sets: &CharacterSets) -> bool {
+        let [lowercase, uppercase, numbers, special, _] = sets.classes();
+        self.contains_in_sets(c, [lowercase, uppercase, numbers, special])
+    }
+
+    /// Returns whether a character belongs to the class, with the lowercase,
+    /// uppercase, digit and special sets.
+    fn contains_in_sets(&self, c: char, [lowercase, uppercase, numbers, special]: [&str; 4]) -> bool {
+        match self {
+            Self::Lowercase => c.is_lowercase() || lowercase.contains(c),
+            Self::Uppercase => c.is_uppercase() || uppercase.contains(c),
+            Self::Number => numbers.contains(c),
+            Self::Special => special.contains(c),
+            Self::Letter => c.is_lowercase() || c.is_uppercase() || lowercase.contains(c) || uppercase.contains(c),
+        }
+    }
+
+    /// Returns whether a specification says the password has a character of
+    /// the class.
+    fn is_specified_by(&self, specification: &PasswordSpecification) -> bool {
+        match self {
+            Self::Lowercase => specification.has_lowercase,
+            Self::Uppercase => specification.has_uppercase,
+            Self::Number => specification.has_number,
+            Self::Special => specification.has_special,
+            Self::Letter => specification.has_lowercase || specification.has_uppercase,
+        }
+    }
+
+    /// Returns the characters used to generate the class, with the default
+    /// character sets.
+    pub(crate) fn charset(&self) -> String {
+        self.charset_in_sets(DEFAULT_CLASSES)
+    }
+
+    /// Returns the characters used to generate the class from the given character sets.
+    pub(crate) fn charset_in(&self, sets: &CharacterSets) -> String {
+        let [lowercase, uppercase, numbers, special, _] = sets.classes();
+        self.charset_in_sets([lowercase, uppercase, numbers, special])
+    }
+
+    /// Returns the characters used to generate the class from the lowercase,
+    /// uppercase, digit and special sets.
+    fn charset_in_sets(&self, [lowercase, uppercase, numbers, special]: [&str; 4]) -> String {
+        match self {
+            Self::Lowercase => lowercase.to_string(),
+            Self::Uppercase => uppercase.to_string(),
+            Self::Number => numbers.to_string(),
+            Self::Special => special.to_string(),
+            Self::Letter => format!("{}{}", lowercase, uppercase),
+        }
+    }
+}
+
+/// Lowercase, uppercase, digit and special sets of the default character sets.
+const DEFAULT_CLASSES: [&str; 4] = [LOWERCASE_CHARSET, UPPERCASE_CHARSET, NUMBERS, SPECIAL_CHARSET];
+
+/// Enum representing a rule of a policy that a password breaks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyViolation {
+    /// The password is shorter than `min_length`.
+    TooShort {
+        /// Length of the password.
+        length: usize,
+        /// Minimum length of the policy.
+        min_length: usize,
+    },
+    /// The password is longer than `max_length`.
+    TooLong {
+        /// Length of the password.
+        length: usize,
+        /// Maximum length of the policy.
+        max_length: usize,
+    },
+    /// The password has too few characters of a class.
+    TooFewCharacters {
+        /// Class of the missing characters.
+        class: CharacterClass,
+        /// Number of characters of the class in the password.
+        count: usize,
+        /// Minimum number of characters of the class.
+        min_count: usize,
+    },
+    /// The password contains a forbidden character.
+    ForbiddenCharacter(char),
+    /// The password repeats a character too many times in a row.
+    TooManyConsecutiveRepeats {
+        /// The repeated character.
+        character: char,
+        /// Number of consecutive occurrences.
+        count: usize,
+        /// Maximum number of consecutive occurrences.
+        max_count: usize,
+    },
+    /// The password contains a word from the common words dictionary.
+    ContainsCommonWord,
+    /// The whole password is in the common passwords dictionary, possibly
+    /// written backwards or with l33t substitutions.
+    CommonPassword,
+    /// The password entropy is below `min_entropy`.
+    EntropyTooLow {
+        /// Entropy of the password.
+        entropy: f64,
+        /// Minimum entropy of the policy.
+        min_entropy: f64,
+    },
+    /// The password strength is below `min_strength`.
+    StrengthTooLow {
+        /// Strength of the password.
+        strength: PasswordStrength,
+        /// Minimum strength of the policy.
+        min_strength: PasswordStrength,
+    },
+    /// The common words dictionary could not be read, so the password could
+    /// not be checked against it.
+    DictionaryUnavailable,
+}
+
+/// Structure representing the rules a password has to follow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordPolicy {
+    /// Minimum number of characters.
+    pub min_length: usize,
+    /// Maximum number of characters, if limited.
+    pub max_length: Option<usize>,
+    /// Minimum number of lowercase letters.
+    pub min_lowercase: usize,
+    /// Minimum number of uppercase letters.
+    pub min_uppercase: usize,
+    /// Minimum number of digits.
+    pub min_numbers: usize,
+    /// Minimum number of special characters.
+    pub min_special_chars: usize,
+    /// Minimum number of letters of either case.
+    pub min_letters: usize,
+    /// Characters the password must not contain.
+    pub forbidden_chars: String,
+    /// Maximum number of times a character may appear in a row, if limited.
+    pub max_consecutive_repeats: Option<usize>,
+    /// Whether passwords containing a word from the common words dictionary are banned.
+    pub ban_common_words: bool,
+    /// Whether passwords equal to an entry of the common words dictionary,
+    /// ignoring case, reversal and l33t substitutions, are banned.
+    pub ban_common_passwords: bool,
+    /// Minimum entropy as computed by [`crate::calculate_entropy`], if required.
+    pub min_entropy: Option<f64>,
+    /// Minimum strength as computed by [`crate::check_password_strength`], from
+    /// the entropy with the character sets the password is checked with, if
+    /// required.
+    pub min_strength: Option<PasswordStrength>,
+}
+
+impl Default for PasswordPolicy {
+    /// Returns a policy that only requires the minimum password length.
+    fn default() -> Self {
+        Self {
+            min_length: MIN_PASSWORD_LENGTH,
+            max_length: None,
+            min_lowercase: 0,
+            min_uppercase: 0,
+            min_numbers: 0,
+            min_special_chars: 0,
+            min_letters: 0,
+            forbidden_chars: String::new(),
+            max_consecutive_repeats: None,
+            ban_common_words: false,
+            ban_common_passwords: false,
+            min_entropy: None,
+            min_strength: None,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Validates a password against the policy.
+    ///
+    /// If the common words dictionary cannot be read while it is needed, the
+    /// password is rejected with [`PolicyViolation::DictionaryUnavailable`].
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A string slice representing the password.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the password follows every rule, or an `Err` with all the
+    /// broken rules otherwise.
+    pub fn validate(&self, password: &str) -> Result<(), Vec<PolicyViolation>> {
+        let violations = self
+            .violations(password)
+            .unwrap_or_else(|_| vec![PolicyViolation::DictionaryUnavailable]);
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Lists the rules of the policy a password breaks, with the default
+    /// character sets.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A string slice representing the password.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the broken rules (empty if the password
+    /// is compliant), or an `Err` if the common words dictionary cannot be read.
+    pub fn violations(&self, password: &str) -> Result<Vec<PolicyViolation>, SecurepassError> {
+        self.violations_with_character_sets(password, &CharacterSets::default())
+    }
+
+    /// Lists the rules of the policy a password breaks, counting the classes,
+    /// the entropy and the strength with the given character sets.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - A string slice representing the password.
+    /// * `sets` - The character sets defining the classes, like the
+    ///   `character_sets` of the options the password was generated with.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`PasswordPolicy::violations`].
+    pub fn violations_with_character_sets(
+        &self,
+        password: &str,
+        sets: &CharacterSets,
+    ) -> Result<Vec<PolicyViolation>, SecurepassError> {
+        let password = &normalize(password, Normalization::Nfc);
+        let mut violations = Vec::new();
+        let length = password.chars().count();
+
+        if length < self.min_length {
+            violations.push(PolicyViolation::TooShort {
+                length,
+                min_length: self.min_length,
+            });
+        }
+        if let Some(max_length) = self.max_length {
+            if length > max_length {
+                violations.push(PolicyViolation::TooLong { length, max_length });
+            }
+        }
+
+        let specification = sets.check_password_specification(password);
+        for (class, min_count) in self.class_minimums() {
+            if min_count == 0 || (min_count == 1 && class.is_specified_by(&specification)) {
+                continue;
+            }
+            let count = password.chars().filter(|c| class.contains_in(*c, sets)).count();
+            if count < min_count {
+                violations.push(PolicyViolation::TooFewCharacters { class, count, min_count });
+            }
+        }
+
+        for c in password.chars().filter(|c| self.forbidden_chars.contains(*c)) {
+            let violation = PolicyViolation::ForbiddenCharacter(c);
+            if !violations.contains(&violation) {
+                violations.push(violation);
+            }
+        }
+
+        if let Some(max_count) = self.max_consecutive_repeats {
+            if let Some((character, count)) = longest_run(password) {
+                if count > max_count {
+                    violations.push(PolicyViolation::TooManyConsecutiveRepeats {
+                        character,
+                        count,
+                        max_count,
+                    });
+                }
+            }
+        }
+
+        if self.ban_common_passwords && check_is_breached_password(password)? {
+            violations.push(PolicyViolation::CommonPassword);
+        } else if self.ban_common_words && check_has_common_words(password)? {
+            violations.push(PolicyViolation::ContainsCommonWord);
+        }
+
+        if let Some(min_entropy) = self.min_entropy {
+            let entropy = sets.calculate_entropy(password);
+            if entropy < min_entropy {
+                violations.push(PolicyViolation::EntropyTooLow { entropy, min_entropy });
+            }
+        }
+
+        if let Some(min_strength) = self.min_strength {
+            let strength = strength_from_entropy(password, sets.calculate_entropy(password), &Dictionary::builtin()?);
+            if strength < min_strength {
+                violations.push(PolicyViolation::StrengthTooLow { strength, min_strength });
+            }
+        }
+
+        Ok(violations)
+    }
+
+    /// Checks that the rules of the policy do not contradict each other.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if some password can follow the policy, or an `Err` with
+    /// [`SecurepassError::InvalidPolicy`] describing the contradiction.
+    pub fn check_consistency(&self) -> Result<(), SecurepassError> {
+        self.check_consistency_with_character_sets(&CharacterSets::default())
+    }
+
+    /// Checks that the rules of the policy do not contradict each other when
+    /// the classes are generated from the given character sets.
+    pub(crate) fn check_consistency_with_character_sets(&self, sets: &CharacterSets) -> Result<(), SecurepassError> {
+        let required = self.min_lowercase
+            + self.min_uppercase
+            + self.min_numbers
+            + self.min_special_chars
+            + self.min_letters.saturating_sub(self.min_lowercase + self.min_uppercase);
+
+        if let Some(max_length) = self.max_length {
+            if max_length < self.min_length {
+                return Err(SecurepassError::InvalidPolicy(format!(
+                    "maximum length {} is less than minimum length {}",
+                    max_length, self.min_length
+                )));
+            }
+            if max_length < required {
+                return Err(SecurepassError::InvalidPolicy(format!(
+                    "maximum length {} cannot fit {} required characters",
+                    max_length, required
+                )));
+            }
+        }
+        if self.max_consecutive_repeats == Some(0) {
+            return Err(SecurepassError::InvalidPolicy(String::from(
+                "maximum consecutive repeats must be at least 1",
+            )));
+        }
+        for (class, min_count) in self.class_minimums() {
+            if min_count > 0 && self.allowed_chars(&class.charset_in(sets)).is_empty() {
+                return Err(SecurepassError::InvalidPolicy(format!(
+                    "every {:?} character is forbidden",
+                    class
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the minimum count of every character class.
+    pub(crate) fn class_minimums(&self) -> [(CharacterClass, usize); 5] {
+        [
+            (CharacterClass::Lowercase, self.min_lowercase),
+            (CharacterClass::Uppercase, self.min_uppercase),
+            (CharacterClass::Number, self.min_numbers),
+            (CharacterClass::Special, self.min_special_chars),
+            (CharacterClass::Letter, self.min_letters),
+        ]
+    }
+
+    /// Removes the forbidden characters from a charset.
+    pub(crate) fn allowed_chars(&self, charset: &str) -> String {
+        charset.chars().filter(|c| !self.forbidden_chars.contains(*c)).collect()
+    }
+}
+
+impl PasswordOptions {
+    /// Generates a password that follows the given policy.
+    ///
+    /// Character classes required by the policy are added even if the options
+    /// exclude them, the length is clamped to the policy limits, and the
+    /// required characters are placed by construction before shuffling. The
+    /// classes are drawn from and counted with the `character_sets` of the
+    /// options.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - A reference to the policy the password has to follow.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with a compliant password, or an `Err` with
+    /// [`SecurepassError::InvalidPolicy`] if the policy contradicts itself, or
+    /// [`SecurepassError::UnsatisfiableRequirements`] if no compliant password
+    /// was found.
+    pub fn generate_password_with_policy(&self, policy: &PasswordPolicy) -> Result<String, SecurepassError> {
+        self.generate_password_with_policy_and_rng(policy, &mut thread_rng())
+    }
+
+    /// Generates a password that follows the given policy using the given
+    /// random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - A reference to the policy the password has to follow.
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`PasswordOptions::generate_password_with_policy`].
+    pub fn generate_password_with_policy_and_rng<R: RngCore + CryptoRng>(
+        &self,
+        policy: &PasswordPolicy,
+        rng: &mut R,
+    ) -> Result<String, SecurepassError> {
+        policy.check_consistency_with_character_sets(&self.character_sets)?;
+
+        for _ in 0..MAX_GENERATION_ATTEMPTS {
+            let derived = (&self.passphrase, &self.pronounceable, &self.pin, &self.memorable, &self.phrase);
+            let password = match derived {
+                (None, None, None, None, None) => self.generate_policy_candidate(policy, rng)?,
+                _ => self.generate_password_with_rng(rng)?,
+            };
+            if policy
+                .violations_with_character_sets(&password, &self.character_sets)?
+                .is_empty()
+            {
+                return Ok(password);
+            }
+        }
+
+        Err(SecurepassError::UnsatisfiableRequirements(format!(
+            "no password following the policy was found in {} attempts",
+            MAX_GENERATION_ATTEMPTS
+        )))
+    }
+
+    /// Generates a candidate password with the required characters of a policy.
+    fn generate_policy_candidate<R: RngCore + CryptoRng>(
+        &self,
+        policy: &PasswordPolicy,
+        rng: &mut R,
+    ) -> Result<String, SecurepassError> {
+        let mut length = self.length.max(policy.min_length);
+        if let Some(max_length) = policy.max_length {
+            length = length.min(max_length);
+        }
+
+        let mut charset = policy.allowed_chars(&self.generate_charset());
+        let mut required: Vec<(String, usize)> = Vec::new();
+        for (class, min_count) in policy.class_minimums() {
+            if min_count == 0 {
+                continue;
+            }
+            let class_chars = self.allowed_chars(&policy.allowed_chars(&class.charset_in(&self.character_sets)));
+            if !class_chars.chars().any(|c| charset.contains(c)) {
+                charset.push_str(&class_chars);
+            }
+            let count = match class {
+                CharacterClass::Letter => min_count.saturating_sub(policy.min_lowercase + policy.min_uppercase),
+                _ => min_count,
+            };
+            required.push((class_chars, count));
+        }
+
+        let required: Vec<(Charset, usize)> = required.iter().map(|(set, count)| (Charset::new(set), *count)).collect();
+        let length = length.max(required.iter().map(|(_, count)| count).sum());
+
+        generate_password_with_required_chars(&Charset::new(&charset), &required, length, rng)
+    }
+}
+
+/// Finds the longest run of one repeated character.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password.
+///
+/// # Returns
+///
+/// The repeated character and the length of its run, or `None` for an empty password.
+fn longest_run(password: &str) -> Option<(char, usize)> {
+    let mut longest: Option<(char, usize)> = None;
+    let mut previous = None;
+    let mut run = 0;
+
+    for c in password.chars() {
+        if previous == Some(c) {
+            run += 1;
+        } else {
+            previous = Some(c);
+            run = 1;
+        }
+        if longest.is_none_or(|(_, count)| run > count) {
+            longest = Some((c, run));
+        }
+    }
+
+    longest
+}
+
+/// Checks if a whole password is a common password, ignoring case, reversal
+/// and common l33t substitutions.
+///
+/// # Arguments
+///
+/// * `password` - A string slice representing the password, normalized to NFC.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` with `true` if the password is a common password,
+/// `false` otherwise, or an `Err` if the dictionary cannot be read.
+fn check_is_breached_password(password: &str) -> Result<bool, SecurepassError> {
+    if check_is_common_password(password)? {
+        return Ok(true);
+    }
+    if !check_has_common_words(password)? {
+        return Ok(false);
+    }
+    let length = password.chars().count();
+
+    Ok(find_common_words(password)?.iter().any(|hit| hit.start == 0 && hit.end == length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn strict_policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 12,
+            max_length: Some(16),
+            min_lowercase: 2,
+            min_uppercase: 2,
+            min_numbers: 2,
+            min_special_chars: 3,
+            min_letters: 0,
+            forbidden_chars: String::from("<>{}^"),
+            max_consecutive_repeats: Some(2),
+            ban_common_words: true,
+            ban_common_passwords: false,
+            min_entropy: Some(80.0),
+            min_strength: Some(PasswordStrength::Strong),
+        }
+    }
+
+    #[test]
+    fn test_validate_compliant_password() {
+        assert_eq!(strict_policy().validate("Xk9!mQ2#vL7$pz"), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_reports_every_violation() {
+        let violations = strict_policy().validate("password111<").unwrap_err();
+        assert!(violations.contains(&PolicyViolation::TooFewCharacters {
+            class: CharacterClass::Uppercase,
+            count: 0,
+            min_count: 2,
+        }));
+        assert!(violations.contains(&PolicyViolation::TooFewCharacters {
+            class: CharacterClass::Special,
+            count: 1,
+            min_count: 3,
+        }));
+        assert!(violations.contains(&PolicyViolation::ForbiddenCharacter('<')));
+        assert!(violations.contains(&PolicyViolation::TooManyConsecutiveRepeats {
+            character: '1',
+            count: 3,
+            max_count: 2,
+        }));
+        assert!(violations.contains(&PolicyViolation::ContainsCommonWord));
+        assert!(violations.iter().any(|v| matches!(v, PolicyViolation::EntropyTooLow { .. })));
+        assert!(violations.contains(&PolicyViolation::StrengthTooLow {
+            strength: PasswordStrength::Weak,
+            min_strength: PasswordStrength::Strong,
+        }));
+    }
+
+    #[test]
+    fn test_validate_length() {
+        let policy = PasswordPolicy {
+            min_length: 4,
+            max_length: Some(6),
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.validate("abc"),
+            Err(vec![PolicyViolation::TooShort { length: 3, min_length: 4 }])
+        );
+        assert_eq!(
+            policy.validate("abcdefg"),
+            Err(vec![PolicyViolation::TooLong { length: 7, max_length: 6 }])
+        );
+    }
+
+    #[test]
+    fn test_check_consistency() {
+        let too_short = PasswordPolicy {
+            max_length: Some(8),
+            ..Default::default()
+        };
+        assert!(matches!(too_short.check_consistency(), Err(SecurepassError::InvalidPolicy(_))));
+
+        let all_digits_forbidden = PasswordPolicy {
+            min_numbers: 1,
+            forbidden_chars: NUMBERS.to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(all_digits_forbidden.check_consistency(), Err(SecurepassError::InvalidPolicy(_))));
+        assert!(strict_policy().check_consistency().is_ok());
+    }
+
+    #[test]
+    fn test_generate_password_with_policy() {
+        let policy = strict_policy();
+        let options = PasswordOptions {
+            include_special_chars: false,
+            ..Default::default()
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+
+        for _ in 0..20 {
+            let password = options.generate_password_with_policy_and_rng(&policy, &mut rng).unwrap();
+            assert_eq!(policy.validate(&password), Ok(()));
+            assert!(password.chars().count() >= 12);
+        }
+    }
+
+    #[test]
+    fn test_generate_password_with_policy_and_custom_special_chars() {
+        let policy = PasswordPolicy {
+            min_special_chars: 2,
+            ..Default::default()
+        };
+        let options = PasswordOptions {
+            character_sets: CharacterSets {
+                special: "#~".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+
+        for _ in 0..20 {
+            let password = options
+                .generate_password_with_policy_and_rng(&policy, &mut rng)
+                .unwrap();
+            let specials: Vec<char> = password.chars().filter(|c| !c.is_alphanumeric()).collect();
+            assert!(specials.len() >= 2, "{}", password);
+            assert!(specials.iter().all(|c| "#~".contains(*c)), "{}", password);
+            assert!(policy
+                .violations_with_character_sets(&password, &options.character_sets)
+                .unwrap()
+                .is_empty());
+        }
+
+        let violations = policy
+            .violations_with_character_sets("Tr0ub4dor!!x", &options.character_sets)
+            .unwrap();
+        assert_eq!(
+            violations,
+            vec![PolicyViolation::TooFewCharacters {
+                class: CharacterClass::Special,
+                count: 0,
+                min_count: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_violations_with_character_sets_strength() {
+        let policy = PasswordPolicy {
+            min_strength: Some(PasswordStrength::Strong),
+            ..Default::default()
+        };
+        let sets = CharacterSets {
+            special: "#~".to_string(),
+            ..Default::default()
+        };
+
+        assert!(policy.violations("!QEa4Kta2}wg1").unwrap().is_empty());
+        assert_eq!(
+            policy.violations_with_character_sets("!QEa4Kta2}wg1", &sets).unwrap(),
+            vec![PolicyViolation::StrengthTooLow {
+                strength: PasswordStrength::Medium,
+                min_strength: PasswordStrength::Strong,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_generate_password_with_unsatisfiable_policy() {
+        let policy = PasswordPolicy {
+            max_length: Some(10),
+            min_entropy: Some(200.0),
+            ..Default::default()
+        };
+
+        let result = PasswordOptions::default().generate_password_with_policy(&policy);
+        assert!(matches!(result, Err(SecurepassError::UnsatisfiableRequirements(_))));
+    }
+}
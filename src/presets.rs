@@ -0,0 +1,136 @@
+//! Password policies reproducing common compliance standards.
+
+use crate::PasswordPolicy;
+
+/// Enum representing a verification level of the OWASP Application Security
+/// Verification Standard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsvsLevel {
+    /// Level 1, the minimum for all applications.
+    Level1,
+    /// Level 2, for applications handling sensitive data.
+    Level2,
+    /// Level 3, for the most critical applications.
+    Level3,
+}
+
+/// Enum representing a named policy preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyPreset {
+    /// NIST SP 800-63B memorized secrets: at least 8 characters, at least 64
+    /// characters allowed, checked against a list of common passwords and no
+    /// composition rules.
+    Nist80063b,
+    /// OWASP ASVS 4.0.3 section V2.1: at least 12 characters, up to 128
+    /// characters allowed, checked against a list of common passwords and no
+    /// composition rules. The password rules are the same at every level.
+    OwaspAsvs(AsvsLevel),
+    /// PCI DSS 4.0 requirement 8.3.6: at least 12 characters with both
+    /// letters and numbers.
+    PciDss4,
+}
+
+impl PasswordPolicy {
+    /// Builds the policy of a named preset.
+    ///
+    /// The presets are validated with the same building blocks as the rest of
+    /// the crate: the character classes a preset requires are looked up with
+    /// [`crate::CharacterSets::check_password_specification`], and the
+    /// breached password check uses [`crate::check_has_common_words`], so
+    /// common passwords written backwards or with l33t substitutions are
+    /// rejected too.
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The preset to reproduce.
+    ///
+    /// # Returns
+    ///
+    /// A `PasswordPolicy` with the rules of the preset.
+    pub fn from_preset(preset: PolicyPreset) -> Self {
+        match preset {
+            PolicyPreset::Nist80063b => Self {
+                min_length: 8,
+                max_length: Some(64),
+                ban_common_passwords: true,
+                ..Default::default()
+            },
+            PolicyPreset::OwaspAsvs(_) => Self {
+                min_length: 12,
+                max_length: Some(128),
+                ban_common_passwords: true,
+                ..Default::default()
+            },
+            PolicyPreset::PciDss4 => Self {
+                min_length: 12,
+                min_numbers: 1,
+                min_letters: 1,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{check_has_common_words, check_is_common_password, CharacterClass, PasswordOptions, PolicyViolation};
+
+    #[test]
+    fn test_nist_preset() {
+        let policy = PasswordPolicy::from_preset(PolicyPreset::Nist80063b);
+        assert_eq!(policy.validate("tulip lamp"), Ok(()));
+        assert_eq!(policy.validate("Password"), Err(vec![PolicyViolation::CommonPassword]));
+        assert_eq!(
+            policy.validate("abc"),
+            Err(vec![PolicyViolation::TooShort { length: 3, min_length: 8 }])
+        );
+        assert!(policy.validate(&"a".repeat(64)).is_ok());
+    }
+
+    #[test]
+    fn test_nist_preset_rejects_breached_password_with_substitutions() {
+        let policy = PasswordPolicy::from_preset(PolicyPreset::Nist80063b);
+        assert!(!check_is_common_password("P@ssw0rd").unwrap());
+        assert!(check_has_common_words("P@ssw0rd").unwrap());
+        assert_eq!(policy.validate("P@ssw0rd"), Err(vec![PolicyViolation::CommonPassword]));
+        assert_eq!(policy.validate("tulip lamp"), Ok(()));
+    }
+
+    #[test]
+    fn test_owasp_asvs_preset() {
+        let policy = PasswordPolicy::from_preset(PolicyPreset::OwaspAsvs(AsvsLevel::Level2));
+        assert_eq!(policy, PasswordPolicy::from_preset(PolicyPreset::OwaspAsvs(AsvsLevel::Level1)));
+        assert_eq!(policy.validate("lowercase only words"), Ok(()));
+        assert!(policy.validate("short words").is_err());
+        assert!(policy.validate(&"a".repeat(129)).is_err());
+    }
+
+    #[test]
+    fn test_pci_dss_preset() {
+        let policy = PasswordPolicy::from_preset(PolicyPreset::PciDss4);
+        assert_eq!(policy.validate("letters1234x"), Ok(()));
+        assert_eq!(
+            policy.validate("1234567890123"),
+            Err(vec![PolicyViolation::TooFewCharacters {
+                class: CharacterClass::Letter,
+                count: 0,
+                min_count: 1,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_generate_password_with_presets() {
+        let options = PasswordOptions::default();
+        for preset in [
+            PolicyPreset::Nist80063b,
+            PolicyPreset::OwaspAsvs(AsvsLevel::Level3),
+            PolicyPreset::PciDss4,
+        ] {
+            let policy = PasswordPolicy::from_preset(preset);
+            let password = options.generate_password_with_policy(&policy).unwrap();
+            assert_eq!(policy.validate(&password), Ok(()));
+        }
+    }
+}
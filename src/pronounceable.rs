@@ -0,0 +1,258 @@
+//! Pronounceable password generation from consonant-vowel syllables.
+
+use crate::passphrase::injected_charset;
+use crate::{generate_random_password_with_rng, SecurepassError, MIN_PASSWORD_LENGTH, NUMBERS, SPECIAL_CHARSET};
+use rand::{thread_rng, CryptoRng, Rng, RngCore};
+
+/// Consonants starting a syllable, without the ones that are hard to spell
+/// out loud like "c", "q", "w", "x" and "y".
+const CONSONANTS: &str = "bdfghjklmnprstvz";
+const VOWELS: &str = "aeiou";
+
+/// Structure representing the options for pronounceable password generation.
+///
+/// Words are made of consonant-vowel syllables, like "Tavoku-Remisa-47".
+pub struct PronounceableOptions {
+    /// Number of words in the password.
+    pub word_count: usize,
+    /// Number of syllables in every word.
+    pub syllables_per_word: usize,
+    /// Separator placed between the words and the digits. Its characters
+    /// are never drawn as digits or special characters.
+    pub separator: String,
+    /// Whether to capitalize the first letter of every word.
+    pub capitalize: bool,
+    /// Number of random digits added after the words.
+    pub digits: usize,
+    /// Whether to append a random special character to one of the words.
+    pub include_special_char: bool,
+}
+
+impl Default for PronounceableOptions {
+    /// Returns the default pronounceable password options.
+    fn default() -> Self {
+        Self {
+            word_count: 3,
+            syllables_per_word: 3,
+            separator: String::from("-"),
+            capitalize: true,
+            digits: 2,
+            include_special_char: false,
+        }
+    }
+}
+
+impl PronounceableOptions {
+    /// Generates a pronounceable password based on the specified options.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the generated password if successful, or
+    /// an `Err` with [`SecurepassError::UnsatisfiableRequirements`] if there
+    /// are no words or the words have no syllables,
+    /// [`SecurepassError::TooShort`] if the password would have fewer than 10
+    /// characters, or [`SecurepassError::EmptyCharset`] if the separator
+    /// contains every digit or special character that can be drawn.
+    pub fn generate_pronounceable_password(&self) -> Result<String, SecurepassError> {
+        self.generate_pronounceable_password_with_rng(&mut thread_rng())
+    }
+
+    /// Generates a pronounceable password based on the specified options
+    /// using the given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A mutable reference to a cryptographically secure random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The same `Result` as [`PronounceableOptions::generate_pronounceable_password`].
+    pub fn generate_pronounceable_password_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<String, SecurepassError> {
+        if self.word_count == 0 || self.syllables_per_word == 0 {
+            return Err(SecurepassError::UnsatisfiableRequirements(String::from(
+                "pronounceable passwords need at least one word of one syllable",
+            )));
+        }
+        let length = self.length();
+        if length < MIN_PASSWORD_LENGTH {
+            return Err(SecurepassError::TooShort {
+                length,
+                min_length: MIN_PASSWORD_LENGTH,
+            });
+        }
+
+        let mut segments: Vec<String> = Vec::with_capacity(self.word_count + 1);
+        for _ in 0..self.word_count {
+            let mut word = String::with_capacity(self.syllables_per_word * 2);
+            for _ in 0..self.syllables_per_word {
+                word.push_str(&generate_random_password_with_rng(CONSONANTS, 1, rng)?);
+                word.push_str(&generate_random_password_with_rng(VOWELS, 1, rng)?);
+            }
+            if self.capitalize {
+                word[..1].make_ascii_uppercase();
+            }
+            segments.push(word);
+        }
+        if self.include_special_char && !segments.is_empty() {
+            let special_chars = injected_charset(SPECIAL_CHARSET, &self.separator);
+            let index = rng.gen_range(0..segments.len());
+            segments[index].push_str(&generate_random_password_with_rng(&special_chars, 1, rng)?);
+        }
+        if self.digits > 0 {
+            let numbers = injected_charset(NUMBERS, &self.separator);
+            segments.push(generate_random_password_with_rng(&numbers, self.digits, rng)?);
+        }
+
+        Ok(segments.join(&self.separator))
+    }
+
+    /// Calculates the entropy of passwords generated with these options.
+    ///
+    /// Every syllable counts as one symbol out of all consonant-vowel pairs,
+    /// and the special character adds the bits of its value and of the word
+    /// it was appended to. Characters of the separator are never drawn, so
+    /// they do not count. This is much lower than what
+    /// [`crate::calculate_entropy`] reports for the same password, which
+    /// assumes every character was picked independently.
+    ///
+    /// # Returns
+    ///
+    /// The entropy in bits as a `f64`.
+    pub fn calculate_entropy(&self) -> f64 {
+        let syllables = (CONSONANTS.len() * VOWELS.len()) as f64;
+        let mut entropy = (self.word_count * self.syllables_per_word) as f64 * syllables.log2();
+
+        let numbers = injected_charset(NUMBERS, &self.separator).chars().count();
+        if numbers > 0 {
+            entropy += self.digits as f64 * (numbers as f64).log2();
+        }
+        let special_chars = injected_charset(SPECIAL_CHARSET, &self.separator).chars().count();
+        if self.include_special_char && self.word_count > 0 && special_chars > 0 {
+            entropy += ((special_chars * self.word_count) as f64).log2();
+        }
+
+        entropy
+    }
+
+    /// Returns the number of characters of the generated passwords.
+    fn length(&self) -> usize {
+        let letters = self.word_count * self.syllables_per_word * 2;
+        let special = usize::from(self.include_special_char && self.word_count > 0);
+        let segments = self.word_count + usize::from(self.digits > 0);
+        let separators = segments.saturating_sub(1) * self.separator.chars().count();
+
+        letters + special + self.digits + separators
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calculate_entropy, PasswordOptions};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_generate_pronounceable_password() {
+        let options = PronounceableOptions::default();
+
+        let password = options.generate_pronounceable_password().unwrap();
+        let segments: Vec<&str> = password.split('-').collect();
+        assert_eq!(segments.len(), 4);
+        assert_eq!(password.len(), options.length());
+        for word in &segments[..3] {
+            let chars: Vec<char> = word.to_lowercase().chars().collect();
+            assert_eq!(chars.len(), 6);
+            assert!(word.starts_with(|c: char| c.is_ascii_uppercase()));
+            for syllable in chars.chunks(2) {
+                assert!(CONSONANTS.contains(syllable[0]) && VOWELS.contains(syllable[1]));
+            }
+        }
+        assert!(segments[3].len() == 2 && segments[3].chars().all(|c| NUMBERS.contains(c)));
+    }
+
+    #[test]
+    fn test_generate_pronounceable_password_with_special_char() {
+        let options = PronounceableOptions {
+            digits: 0,
+            include_special_char: true,
+            separator: String::new(),
+            ..Default::default()
+        };
+
+        let password = options
+            .generate_pronounceable_password_with_rng(&mut ChaCha20Rng::seed_from_u64(4))
+            .unwrap();
+        assert_eq!(password.chars().filter(|c| SPECIAL_CHARSET.contains(*c)).count(), 1);
+        assert!(!password.chars().any(|c| NUMBERS.contains(c)));
+        assert_eq!(password.len(), 19);
+    }
+
+    #[test]
+    fn test_generate_pronounceable_password_keeps_separators() {
+        let options = PronounceableOptions {
+            include_special_char: true,
+            ..Default::default()
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(9);
+
+        for _ in 0..200 {
+            let password = options.generate_pronounceable_password_with_rng(&mut rng).unwrap();
+            assert_eq!(password.split('-').count(), 4, "{}", password);
+            assert_eq!(password.len(), options.length());
+        }
+        let expected = 9.0 * 80f64.log2() + 2.0 * 10f64.log2() + (20.0 * 3.0f64).log2();
+        assert!((options.calculate_entropy() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_generate_pronounceable_password_too_short() {
+        let options = PronounceableOptions {
+            word_count: 1,
+            digits: 0,
+            ..Default::default()
+        };
+
+        let result = options.generate_pronounceable_password();
+        assert!(matches!(result, Err(SecurepassError::TooShort { length: 6, min_length: 10 })));
+    }
+
+    #[test]
+    fn test_generate_pronounceable_password_without_syllables() {
+        for (word_count, syllables_per_word) in [(5, 0), (0, 3)] {
+            let options = PronounceableOptions {
+                word_count,
+                syllables_per_word,
+                digits: 10,
+                ..Default::default()
+            };
+
+            let result = options.generate_pronounceable_password();
+            assert!(matches!(result, Err(SecurepassError::UnsatisfiableRequirements(_))));
+        }
+    }
+
+    #[test]
+    fn test_calculate_pronounceable_entropy() {
+        let options = PronounceableOptions::default();
+        let expected = 9.0 * 80f64.log2() + 2.0 * 10f64.log2();
+        assert!((options.calculate_entropy() - expected).abs() < 1e-9);
+
+        let password = options.generate_pronounceable_password().unwrap();
+        assert!(options.calculate_entropy() < calculate_entropy(&password));
+    }
+
+    #[test]
+    fn test_generate_password_with_pronounceable() {
+        let options = PasswordOptions {
+            pronounceable: Some(PronounceableOptions::default()),
+            ..Default::default()
+        };
+
+        let password = options.generate_password().unwrap();
+        assert_eq!(password.split('-').count(), 4);
+    }
+}
@@ -0,0 +1,467 @@
+//! Stateless site passwords derived from a master password.
+//!
+//! The same master password, site, login and counter always give the same
+//! password, so nothing has to be stored: changing the counter rotates the
+//! password of one site without touching the others.
+//!
+//! The derivation of a version only depends on scrypt, the ChaCha20 stream
+//! and the fixed sampler of this module, never on the helpers shared with
+//! the rest of the crate, so changes to random generation elsewhere cannot
+//! change site passwords.
+
+use crate::{normalize, Normalization, PasswordOptions, SecurepassError, MIN_PASSWORD_LENGTH};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+/// Character classes of the first version, in the order their required
+/// characters are drawn: lowercase, uppercase, digits and special characters.
+const V1_CLASSES: [&str; 4] = [
+    "abcdefghijklmnopqrstuvwxyz",
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+    "0123456789",
+    "!@#$%^&*?(){}[]<>-_=+",
+];
+/// Characters removed from the classes of the first version with `exclude_ambiguous`.
+const V1_AMBIGUOUS: &str = "B8G6I1l|0OQDS5Z2(){}[]<>";
+/// Minimum length of a balanced site password of the first version.
+const V1_MIN_BALANCED_LENGTH: usize = 13;
+/// Entropy in bits a balanced site password of the first version reaches.
+const V1_BALANCED_ENTROPY: f64 = 82.0;
+
+/// Enum representing the versions of the site password derivation.
+///
+/// A version never changes once released, so passwords derived with it stay
+/// the same across releases of the crate. New versions are added instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum SiteAlgorithm {
+    /// scrypt with `N = 2^15`, `r = 8` and `p = 1` over the master password,
+    /// site and login taken as they are, keying ChaCha20, whose 32-bit words
+    /// draw the characters from fixed character sets by rejection sampling.
+    /// The password is not checked against the dictionary.
+    #[default]
+    V1,
+    /// The first version over the master password, site and login
+    /// normalized to NFC, so that the same text typed with precomposed or
+    /// combining accents gives the same password.
+    V2,
+}
+
+impl SiteAlgorithm {
+    /// Returns the scrypt cost parameters of the version, with a 32-byte output.
+    fn scrypt_params(self) -> scrypt::Params {
+        match self {
+            Self::V1 | Self::V2 => scrypt::Params::new(15, 8, 1, 32).expect("the scrypt parameters are valid"),
+        }
+    }
+
+    /// Returns the domain separation prefix of the salt of the version.
+    fn salt_prefix(self) -> &'static [u8] {
+        match self {
+            Self::V1 => b"securepass-site-v1",
+            Self::V2 => b"securepass-site-v2",
+        }
+    }
+
+    /// Returns a text of the derivation as the version reads it.
+    fn prepare(self, text: &str) -> String {
+        match self {
+            Self::V1 => text.to_string(),
+            Self::V2 => normalize(text, Normalization::Nfc),
+        }
+    }
+}
+
+/// Structure representing the site a password is derived for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiteOptions {
+    /// Name of the site, like "example.com". Surrounding whitespace is
+    /// removed and it is lowercased, so "Example.com " gives the same password.
+    pub site: String,
+    /// Login used on the site, taken as is apart from the normalization of
+    /// the algorithm.
+    pub login: String,
+    /// Counter incremented to rotate the password of the site.
+    pub counter: u32,
+    /// Version of the derivation.
+    pub algorithm: SiteAlgorithm,
+}
+
+impl Default for SiteOptions {
+    /// Returns the site options with an empty site and login and the first counter.
+    fn default() -> Self {
+        Self {
+            site: String::new(),
+            login: String::new(),
+            counter: 1,
+            algorithm: SiteAlgorithm::default(),
+        }
+    }
+}
+
+impl SiteOptions {
+    /// Builds the salt binding the derived key to the site, login and counter.
+    fn salt(&self) -> Vec<u8> {
+        let site = self.algorithm.prepare(self.site.trim()).to_lowercase();
+        let login = self.algorithm.prepare(&self.login);
+        let mut salt = self.algorithm.salt_prefix().to_vec();
+        for field in [site.as_bytes(), login.as_bytes()] {
+            salt.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            salt.extend_from_slice(field);
+        }
+        salt.extend_from_slice(&self.counter.to_be_bytes());
+        salt
+    }
+}
+
+/// Structure representing the fixed sampler of site passwords, drawing
+/// uniform indexes from the 32-bit words of ChaCha20.
+struct SiteSampler {
+    stream: ChaCha20Rng,
+}
+
+impl SiteSampler {
+    /// Returns a uniform index below `bound`, rejecting the words of the
+    /// last incomplete multiple of `bound` so no index is more likely.
+    fn index(&mut self, bound: usize) -> usize {
+        let bound = bound as u64;
+        let limit = (1u64 << 32) - (1u64 << 32) % bound;
+        loop {
+            let word = u64::from(self.stream.next_u32());
+            if word < limit {
+                return (word % bound) as usize;
+            }
+        }
+    }
+}
+
+impl PasswordOptions {
+    /// Derives the password of a site from a master password.
+    ///
+    /// The master password and site are stretched with the memory-hard KDF
+    /// of the algorithm, whose output keys the fixed sampler of the version.
+    /// The password follows the length, classes, minimum counts and
+    /// exclusions of the options, over the fixed character sets of the
+    /// version. When balancing, it has every included class and is long
+    /// enough to reach the entropy of a strong password, but it is not
+    /// checked against the dictionary, so that a new dictionary cannot
+    /// change it.
+    ///
+    /// # Arguments
+    ///
+    /// * `master_password` - A string slice representing the master password.
+    /// * `site` - The site, login, counter and algorithm.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the derived password, or an `Err` with
+    /// [`SecurepassError::TooShort`] if the password length is less than 10,
+    /// [`SecurepassError::UnsatisfiableRequirements`] if the master password
+    /// is empty, the options use a phrase, passphrase, pronounceable, PIN or
+    /// memorable mode, Unicode characters or character sets other than the
+    /// ones of the version, or the minimum counts do not fit in the length,
+    /// or [`SecurepassError::EmptyCharset`] if every character of a used
+    /// class is excluded.
+    pub fn derive_site_password(&self, master_password: &str, site: &SiteOptions) -> Result<String, SecurepassError> {
+        if master_password.is_empty() {
+            return Err(SecurepassError::UnsatisfiableRequirements(String::from(
+                "the master password is empty",
+            )));
+        }
+        if self.phrase.is_some()
+            || self.passphrase.is_some()
+            || self.pronounceable.is_some()
+            || self.pin.is_some()
+            || self.memorable.is_some()
+        {
+            return Err(SecurepassError::UnsatisfiableRequirements(String::from(
+                "site passwords are only derived from character options",
+            )));
+        }
+        if self.include_unicode || self.character_sets.classes()[..4] != V1_CLASSES {
+            return Err(SecurepassError::UnsatisfiableRequirements(String::from(
+                "site passwords only use the character sets of their algorithm",
+            )));
+        }
+        if self.length < MIN_PASSWORD_LENGTH {
+            return Err(SecurepassError::TooShort {
+                length: self.length,
+                min_length: MIN_PASSWORD_LENGTH,
+            });
+        }
+
+        let mut key = [0u8; 32];
+        let master_password = site.algorithm.prepare(master_password);
+        scrypt::scrypt(
+            master_password.as_bytes(),
+            &site.salt(),
+            &site.algorithm.scrypt_params(),
+            &mut key,
+        )
+        .expect("the output length is valid");
+        let mut sampler = SiteSampler {
+            stream: ChaCha20Rng::from_seed(key),
+        };
+
+        self.sample_site_password(&mut sampler)
+    }
+
+    /// Draws a site password with the sampler of the first version.
+    ///
+    /// The required characters of every class are drawn first, in class
+    /// order, each at a position picked uniformly among the free ones by a
+    /// partial Fisher-Yates shuffle of the positions. The free positions are
+    /// then filled in increasing order from the union of the included classes.
+    fn sample_site_password(&self, sampler: &mut SiteSampler) -> Result<String, SecurepassError> {
+        let included = [
+            true,
+            self.include_uppercase || self.min_uppercase > 0,
+            self.include_numbers || self.min_numbers > 0,
+            self.include_special_chars || self.min_special_chars > 0,
+        ];
+        let minimums = [
+            self.min_lowercase,
+            self.min_uppercase,
+            self.min_numbers,
+            self.min_special_chars,
+        ];
+
+        let mut classes: Vec<(Vec<char>, usize)> = Vec::with_capacity(V1_CLASSES.len());
+        for ((set, included), minimum) in V1_CLASSES.into_iter().zip(included).zip(minimums) {
+            if !included {
+                continue;
+            }
+            let chars: Vec<char> = set
+                .chars()
+                .filter(|c| !self.excluded_chars.contains(*c))
+                .filter(|c| !(self.exclude_ambiguous && V1_AMBIGUOUS.contains(*c)))
+                .collect();
+            if chars.is_empty() && minimum > 0 {
+                return Err(SecurepassError::EmptyCharset);
+            }
+            let minimum = minimum.max(usize::from(self.with_balancing && !chars.is_empty()));
+            classes.push((chars, minimum));
+        }
+        let pool: Vec<char> = classes.iter().flat_map(|(chars, _)| chars.iter().copied()).collect();
+        if pool.is_empty() {
+            return Err(SecurepassError::EmptyCharset);
+        }
+
+        let mut length = self.length;
+        if self.with_balancing {
+            let bits_per_char = (pool.len() as f64).log2();
+            let entropy_length = if bits_per_char > 0.0 {
+                (V1_BALANCED_ENTROPY / bits_per_char).ceil() as usize
+            } else {
+                0
+            };
+            length = length.max(V1_MIN_BALANCED_LENGTH).max(entropy_length);
+        }
+        let required_count: usize = classes.iter().map(|(_, minimum)| minimum).sum();
+        if required_count > length {
+            return Err(SecurepassError::UnsatisfiableRequirements(format!(
+                "{} required characters do not fit in {} characters",
+                required_count, length
+            )));
+        }
+
+        let mut password: Vec<Option<char>> = vec![None; length];
+        let mut positions: Vec<usize> = (0..length).collect();
+        let mut placed = 0;
+        for (chars, minimum) in &classes {
+            for _ in 0..*minimum {
+                let swap = placed + sampler.index(length - placed);
+                positions.swap(placed, swap);
+                password[positions[placed]] = Some(chars[sampler.index(chars.len())]);
+                placed += 1;
+            }
+        }
+        for c in password.iter_mut().filter(|c| c.is_none()) {
+            *c = Some(pool[sampler.index(pool.len())]);
+        }
+
+        Ok(password.into_iter().flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check_password_specification;
+
+    fn site(name: &str, counter: u32) -> SiteOptions {
+        SiteOptions {
+            site: String::from(name),
+            login: String::from("alice@example.com"),
+            counter,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_derive_site_password() {
+        let options = PasswordOptions::default();
+
+        let password = options
+            .derive_site_password("master secret", &site("example.com", 1))
+            .unwrap();
+        assert_eq!(
+            password,
+            options
+                .derive_site_password("master secret", &site(" Example.COM", 1))
+                .unwrap()
+        );
+        assert_eq!(password.chars().count(), 13);
+        let specification = check_password_specification(&password);
+        assert!(specification.has_lowercase && specification.has_uppercase);
+        assert!(specification.has_number && specification.has_special);
+
+        assert_ne!(
+            password,
+            options
+                .derive_site_password("master secret", &site("example.com", 2))
+                .unwrap()
+        );
+        assert_ne!(
+            password,
+            options
+                .derive_site_password("master secreT", &site("example.com", 1))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_derive_site_password_known_answers() {
+        let options = PasswordOptions::default();
+        assert_eq!(
+            options
+                .derive_site_password("correct horse", &site("example.org", 1))
+                .unwrap(),
+            "Dbh(qqE(Y7r2}"
+        );
+
+        let unicode_site = SiteOptions {
+            site: String::from("Bücher.example"),
+            login: String::from("zoë"),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.derive_site_password("pässwörd ✓", &unicode_site).unwrap(),
+            "bJ3[7Ow{j2U$}"
+        );
+
+        let options = PasswordOptions {
+            length: 24,
+            include_special_chars: false,
+            with_balancing: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            options
+                .derive_site_password("correct horse", &site("example.org", 1))
+                .unwrap(),
+            "kTdwgGcefiuClOjgjxy4lNsg"
+        );
+
+        let options = PasswordOptions {
+            length: 16,
+            min_numbers: 3,
+            min_special_chars: 2,
+            exclude_ambiguous: true,
+            excluded_chars: String::from("xyz"),
+            ..Default::default()
+        };
+        assert_eq!(
+            options
+                .derive_site_password("correct horse", &site("example.org", 7))
+                .unwrap(),
+            "9!E9kFs_7iYe*^3m"
+        );
+    }
+
+    #[test]
+    fn test_derive_site_password_v2_normalizes() {
+        let options = PasswordOptions::default();
+        let composed = SiteOptions {
+            site: String::from("caf\u{e9}.example"),
+            login: String::from("zo\u{eb}"),
+            algorithm: SiteAlgorithm::V2,
+            ..Default::default()
+        };
+        let decomposed = SiteOptions {
+            site: String::from("cafe\u{301}.example"),
+            login: String::from("zoe\u{308}"),
+            ..composed.clone()
+        };
+
+        let password = options.derive_site_password("p\u{e4}ssw\u{f6}rd", &composed).unwrap();
+        assert_eq!(password, "rt3TsUIbFV6c]");
+        assert_eq!(
+            password,
+            options
+                .derive_site_password("pa\u{308}sswo\u{308}rd", &decomposed)
+                .unwrap()
+        );
+
+        let v1 = SiteOptions {
+            algorithm: SiteAlgorithm::V1,
+            ..decomposed
+        };
+        assert_ne!(
+            password,
+            options.derive_site_password("pa\u{308}sswo\u{308}rd", &v1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sampler_rejects_incomplete_range() {
+        let mut sampler = SiteSampler {
+            stream: ChaCha20Rng::from_seed([7; 32]),
+        };
+        for bound in [1, 3, 62, 72, 1 << 31] {
+            assert!(sampler.index(bound) < bound);
+        }
+    }
+
+    #[test]
+    fn test_salt_separates_fields() {
+        let first = SiteOptions {
+            site: String::from("ab"),
+            login: String::from("c"),
+            ..Default::default()
+        };
+        let second = SiteOptions {
+            site: String::from("a"),
+            login: String::from("bc"),
+            ..Default::default()
+        };
+        assert_ne!(first.salt(), second.salt());
+    }
+
+    #[test]
+    fn test_derive_site_password_invalid_options() {
+        let options = PasswordOptions::default();
+        assert!(matches!(
+            options.derive_site_password("", &site("example.com", 1)),
+            Err(SecurepassError::UnsatisfiableRequirements(_))
+        ));
+
+        let options = PasswordOptions {
+            phrase: Some(String::from("abc")),
+            ..Default::default()
+        };
+        assert!(matches!(
+            options.derive_site_password("master secret", &site("example.com", 1)),
+            Err(SecurepassError::UnsatisfiableRequirements(_))
+        ));
+
+        let options = PasswordOptions {
+            include_unicode: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            options.derive_site_password("master secret", &site("example.com", 1)),
+            Err(SecurepassError::UnsatisfiableRequirements(_))
+        ));
+    }
+}
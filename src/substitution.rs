@@ -0,0 +1,201 @@
+//! Tables of the symbols commonly substituted for letters, like "@" for "a".
+
+/// Symbols with the letters they commonly replace.
+const DEFAULT_SUBSTITUTIONS: [(char, &str); 17] = [
+    ('4', "a"),
+    ('@', "a"),
+    ('8', "b"),
+    ('(', "c"),
+    ('{', "c"),
+    ('3', "e"),
+    ('6', "g"),
+    ('9', "g"),
+    ('1', "il"),
+    ('!', "i"),
+    ('|', "il"),
+    ('0', "o"),
+    ('$', "s"),
+    ('5', "s"),
+    ('7', "lt"),
+    ('+', "t"),
+    ('2', "z"),
+];
+
+/// Maximum number of ways of reading the symbols of a password that are tried.
+const MAX_COMBINATIONS: usize = 256;
+
+/// Structure representing a table of l33t substitutions, mapping symbols to
+/// the letters they can stand for.
+///
+/// The default table covers the common substitutions, like "@" and "4" for
+/// "a" or "0" for "o".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubstitutionTable {
+    /// Symbols with the lowercase letters they replace, sorted by symbol.
+    entries: Vec<(char, Vec<char>)>,
+}
+
+impl Default for SubstitutionTable {
+    /// Returns the table of common substitutions.
+    fn default() -> Self {
+        DEFAULT_SUBSTITUTIONS
+            .iter()
+            .fold(Self::new(), |table, (symbol, letters)| table.with_substitution(*symbol, letters))
+    }
+}
+
+impl SubstitutionTable {
+    /// Creates a table without any substitution, which disables l33t matching.
+    ///
+    /// # Returns
+    ///
+    /// An empty `SubstitutionTable`.
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Adds letters a symbol can stand for.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The character written instead of a letter, like '@'.
+    /// * `letters` - The letters the symbol can replace, like "a". They are
+    ///   added to the letters already known for the symbol.
+    ///
+    /// # Returns
+    ///
+    /// The extended `SubstitutionTable`.
+    pub fn with_substitution(mut self, symbol: char, letters: &str) -> Self {
+        let position = match self.entries.binary_search_by_key(&symbol, |(key, _)| *key) {
+            Ok(position) => position,
+            Err(position) => {
+                self.entries.insert(position, (symbol, Vec::new()));
+                position
+            }
+        };
+        let known = &mut self.entries[position].1;
+        for letter in letters.chars().flat_map(char::to_lowercase) {
+            if letter != symbol && !known.contains(&letter) {
+                known.push(letter);
+            }
+        }
+        if known.is_empty() {
+            self.entries.remove(position);
+        }
+
+        self
+    }
+
+    /// Returns the letters a symbol can stand for.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The character written instead of a letter.
+    ///
+    /// # Returns
+    ///
+    /// The letters, empty if the symbol is not in the table.
+    pub fn letters(&self, symbol: char) -> &[char] {
+        self.entries
+            .binary_search_by_key(&symbol, |(key, _)| *key)
+            .map_or(&[], |position| &self.entries[position].1)
+    }
+
+    /// Returns the symbols that can stand for a letter.
+    ///
+    /// # Arguments
+    ///
+    /// * `letter` - The letter, matched without case.
+    ///
+    /// # Returns
+    ///
+    /// The symbols in the order of the table, empty if no symbol replaces the letter.
+    pub fn symbols(&self, letter: char) -> Vec<char> {
+        let letter = letter.to_lowercase().next().unwrap_or(letter);
+
+        self.entries
+            .iter()
+            .filter(|(_, letters)| letters.contains(&letter))
+            .map(|(symbol, _)| *symbol)
+            .collect()
+    }
+
+    /// Returns the number of symbols in the table.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the table has no substitution.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Lists every way of reading the symbols of the table present in a text.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - The characters of the text.
+    ///
+    /// # Returns
+    ///
+    /// The readings as `(symbol, letter)` pairs, each with at least one pair,
+    /// limited to the first 256.
+    pub(crate) fn combinations(&self, chars: &[char]) -> Vec<Vec<(char, char)>> {
+        let mut combinations: Vec<Vec<(char, char)>> = vec![Vec::new()];
+
+        for (symbol, letters) in self.entries.iter().filter(|(symbol, _)| chars.contains(symbol)) {
+            combinations = combinations
+                .iter()
+                .flat_map(|combination| {
+                    letters.iter().map(move |letter| {
+                        let mut extended = combination.clone();
+                        extended.push((*symbol, *letter));
+                        extended
+                    })
+                })
+                .take(MAX_COMBINATIONS)
+                .collect();
+        }
+
+        combinations.retain(|combination| !combination.is_empty());
+        combinations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_table() {
+        let table = SubstitutionTable::default();
+        assert_eq!(table.len(), DEFAULT_SUBSTITUTIONS.len());
+        assert_eq!(table.letters('1'), &['i', 'l']);
+        assert!(table.letters('x').is_empty());
+        assert_eq!(table.symbols('A'), vec!['4', '@']);
+        assert_eq!(table.symbols('l'), vec!['1', '7', '|']);
+        assert!(table.symbols('x').is_empty());
+    }
+
+    #[test]
+    fn test_with_substitution() {
+        let table = SubstitutionTable::new()
+            .with_substitution('#', "H")
+            .with_substitution('#', "hn")
+            .with_substitution('%', "");
+        assert_eq!(table.letters('#'), &['h', 'n']);
+        assert_eq!(table.len(), 1);
+        assert!(SubstitutionTable::new().is_empty());
+    }
+
+    #[test]
+    fn test_combinations() {
+        let table = SubstitutionTable::default();
+        let chars: Vec<char> = "p@1".chars().collect();
+        assert_eq!(
+            table.combinations(&chars),
+            vec![vec![('1', 'i'), ('@', 'a')], vec![('1', 'l'), ('@', 'a')]]
+        );
+        assert!(table.combinations(&['a', 'b']).is_empty());
+    }
+}